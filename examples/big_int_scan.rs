@@ -0,0 +1,15 @@
+use std::io::{BufRead, Cursor};
+
+use math::big::Int;
+
+fn main() {
+    let mut r = Cursor::new("-0x_dead_beef, 42");
+    let mut i = Int::default();
+
+    let (base, n) = i.scan(&mut r, 0).unwrap();
+    assert_eq!(i, Int::new(-0xdeadbeef));
+    assert_eq!((base, n), (16, 13));
+
+    // the separator is left in the reader
+    assert_eq!(r.fill_buf().unwrap(), b", 42");
+}
@@ -2,7 +2,7 @@ use math::big::Int;
 
 fn main() {
     let mut i = Int::default();
-    i.set_string("644", 8);
+    i.set_string("644", 8).unwrap();
 
     assert_eq!(i, Int::new(420));
 }
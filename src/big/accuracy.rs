@@ -0,0 +1,40 @@
+use std::fmt::{self, Display};
+use std::ops::Neg;
+
+/// Describes the rounding error produced by the most recent operation that generated
+/// a value, relative to the exact value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Accuracy {
+    /// The result is less than the exact value.
+    Below = -1,
+    /// The result is the exact value.
+    Exact = 0,
+    /// The result is greater than the exact value.
+    Above = 1,
+}
+
+impl Display for Accuracy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            Self::Below => "Below",
+            Self::Exact => "Exact",
+            Self::Above => "Above",
+        };
+
+        f.write_str(s)
+    }
+}
+
+/// Flips the direction of the rounding error, as happens when the rounded value
+/// is negated.
+impl Neg for Accuracy {
+    type Output = Accuracy;
+
+    fn neg(self) -> Accuracy {
+        match self {
+            Self::Below => Self::Above,
+            Self::Exact => Self::Exact,
+            Self::Above => Self::Below,
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::io::{self, BufRead};
+use std::str::FromStr;
+
+use num_bigint::BigInt;
+
+use super::Int;
+
+/// The error type returned when a checked conversion from [Int] to a primitive
+/// integer type fails because the value doesn't fit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TryFromIntError(());
+
+/// The error type returned when parsing an [Int] from a string fails, e.g. by
+/// [Int::set_string]. Indices count bytes from the start of the whole input,
+/// including any sign and base prefix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseIntError {
+    /// The input contains no digits, e.g. `""`, `"-"` or `"0x"`.
+    Empty,
+    /// The base is neither 0 nor within `[2, MAX_BASE]`.
+    InvalidBase,
+    /// The byte at `index` isn't a valid digit for the base.
+    InvalidDigit { index: usize, byte: u8 },
+    /// The underscore at `index` doesn't separate a base prefix or digit from a digit.
+    BadUnderscore { index: usize },
+}
+
+macro_rules! impl_primitive_conv {
+    ($($t:ty)*) => {$(
+        impl From<$t> for Int {
+            fn from(x: $t) -> Self {
+                Self(BigInt::from(x))
+            }
+        }
+
+        impl TryFrom<&Int> for $t {
+            type Error = TryFromIntError;
+
+            fn try_from(x: &Int) -> Result<Self, Self::Error> {
+                <$t>::try_from(&x.0).map_err(|_| TryFromIntError(()))
+            }
+        }
+
+        impl TryFrom<Int> for $t {
+            type Error = TryFromIntError;
+
+            fn try_from(x: Int) -> Result<Self, Self::Error> {
+                <$t>::try_from(&x)
+            }
+        }
+    )*};
+}
+
+impl_primitive_conv!(i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize);
+
+impl Display for TryFromIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("out of range integral type conversion attempted")
+    }
+}
+
+impl Error for TryFromIntError {}
+
+impl Display for ParseIntError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => f.write_str("number has no digits"),
+            Self::InvalidBase => f.write_str("invalid number base"),
+            Self::InvalidDigit { index, byte } if byte.is_ascii_graphic() => {
+                write!(f, "bad digit '{}' at index {index}", *byte as char)
+            }
+            Self::InvalidDigit { index, byte } => {
+                write!(f, "bad digit 0x{byte:02x} at index {index}")
+            }
+            Self::BadUnderscore { index } => write!(f, "bad '_' at index {index}"),
+        }
+    }
+}
+
+impl Error for ParseIntError {}
+
+/// Parses `s` following the rules of [Int::set_string] with base 0, i.e. the base
+/// is derived from the prefix of `s` and underscores are allowed as digit separators.
+impl FromStr for Int {
+    type Err = ParseIntError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut out = Int::default();
+        out.set_string(s, 0)?;
+        Ok(out)
+    }
+}
+
+/// Reads a [BufRead] one byte at a time without consuming bytes before they're
+/// accepted, keeping count of the bytes consumed so far.
+pub(super) struct ByteScanner<'a, R> {
+    r: &'a mut R,
+    n: usize,
+}
+
+impl<'a, R: BufRead> ByteScanner<'a, R> {
+    pub(super) fn new(r: &'a mut R) -> Self {
+        Self { r, n: 0 }
+    }
+
+    /// Consumes the byte last returned by [ByteScanner::peek].
+    pub(super) fn consume(&mut self) {
+        self.r.consume(1);
+        self.n += 1;
+    }
+
+    pub(super) fn consumed(&self) -> usize {
+        self.n
+    }
+
+    /// Returns the next byte without consuming it, or `None` at the end of input.
+    pub(super) fn peek(&mut self) -> io::Result<Option<u8>> {
+        loop {
+            match self.r.fill_buf() {
+                Ok(buf) => return Ok(buf.first().copied()),
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            }
+        }
+    }
+}
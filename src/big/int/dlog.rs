@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+
+use super::{crt, factor::factorize, prime::SplitMix64, Int};
+use crate::primes::isqrt;
+
+// Subgroups of prime order up to this many bits are searched by baby steps and
+// giant steps, larger ones by Pollard's rho method.
+const BSGS_MAX_BITS: u64 = 32;
+
+// The number of multipliers the random walk of the rho method picks from.
+const WALK_SIZE: usize = 16;
+
+// A walk of the rho method is given up after this many times √q steps, and the
+// method after this many walks. Within the subgroup of order q, a walk closes
+// after about 1.25·√q steps, and is only this long with a negligible chance.
+const WALK_STEPS: u64 = 16;
+const WALKS: usize = 2;
+
+/// Returns the discrete logarithm of `h` to the base `g` modulo `p`, that is the
+/// least `x ≥ 0` with `gˣ ≡ h (mod p)`, or `None` if `h` isn't a power of `g`.
+/// `order` must be a multiple of the order of `g` in (ℤ/pℤ)*, such as `p - 1` for
+/// a prime `p`, and is factored by [factorize], which doesn't return before the
+/// factorization is complete. If that is out of reach, pass the prime factors
+/// of a known multiple of the order of `g` to [dlog_factored] instead.
+///
+/// The Pohlig-Hellman algorithm reduces the logarithm to ones in the subgroups of
+/// prime order `q` dividing the order of `g`, which are found by baby steps and
+/// giant steps for `q < 2³²` and by Pollard's rho method otherwise, and combines
+/// them by [crt]. The running time is thus governed by `√q` for the largest such
+/// `q`, once `order` is factored.
+///
+/// For composite `p`, (ℤ/pℤ)* needn't be cyclic, and `h` may be of order `q`
+/// without being a power of `g`. In that case, the rho method walks through a
+/// group of order `q²`, and `dlog` gives up with `None` after `32·√q`
+/// steps.
+///
+/// S. C. Pohlig and M. E. Hellman, "An improved algorithm for computing
+/// logarithms over GF(p) and its cryptographic significance", IEEE Transactions
+/// on Information Theory 24(1), 1978, pp. 106-110.
+///
+/// J. M. Pollard, "Monte Carlo methods for index computation (mod p)",
+/// Mathematics of Computation 32(143), 1978, pp. 918-924.
+///
+/// # Panics
+/// If `p ≤ 1`, `g` isn't a unit modulo `p`, `order ≤ 0` or
+/// `g^order ≢ 1 (mod p)`.
+pub fn dlog(g: &Int, h: &Int, p: &Int, order: &Int) -> Option<Int> {
+    assert!(order.0.is_positive(), "order must be positive");
+    dlog_factored(g, h, p, &factorize(order))
+}
+
+/// Returns the discrete logarithm of `h` to the base `g` modulo `p` like [dlog],
+/// but takes the order as its factorization into primes `q` with exponents `e`,
+/// as returned by [factorize]. The product of the `qᵉ` must be a multiple of the
+/// order of `g` in (ℤ/pℤ)*, such as the prime order of a subgroup `g` is known to
+/// generate, which spares factoring `p - 1` in full.
+///
+/// # Panics
+/// If `p ≤ 1`, `g` isn't a unit modulo `p`, some `q ≤ 1`, or the product of the
+/// `qᵉ` isn't a multiple of the order of `g`.
+pub fn dlog_factored(g: &Int, h: &Int, p: &Int, order: &[(Int, u32)]) -> Option<Int> {
+    assert!(p.0 > BigInt::one(), "modulus must be greater than 1");
+
+    let reduce = |x: &Int| x.0.mod_floor(&p.0).to_biguint().expect("nonnegative");
+    let (g, h) = (reduce(g), reduce(h));
+    let p = p.0.magnitude();
+    assert!(g.gcd(p).is_one(), "g must be a unit modulo p");
+
+    let mut factors: Vec<(BigUint, u32)> = order
+        .iter()
+        .map(|(q, e)| {
+            assert!(q.0 > BigInt::one(), "order factors must be primes");
+            (q.0.magnitude().clone(), *e)
+        })
+        .collect();
+    let n: BigUint = factors.iter().map(|(q, e)| q.pow(*e)).product();
+    assert!(
+        g.modpow(&n, p).is_one(),
+        "order must be a multiple of the order of g"
+    );
+
+    // strip the primes from the order as long as g^(m/q) ≡ 1 still holds
+    let mut m = n;
+    for (q, e) in factors.iter_mut() {
+        while *e > 0 && g.modpow(&(&m / &*q), p).is_one() {
+            m /= &*q;
+            *e -= 1;
+        }
+    }
+
+    let mut g_inv = Int::default();
+    g_inv
+        .mod_inverse(&Int(g.clone().into()), &Int(p.clone().into()))
+        .expect("g is a unit");
+    let g_inv = g_inv.0.magnitude().clone();
+
+    let (mut residues, mut moduli) = (Vec::new(), Vec::new());
+    for (q, e) in factors.into_iter().filter(|&(_, e)| e > 0) {
+        // g^c generates the subgroup of order q^e
+        let qe = q.pow(e);
+        let c = &m / &qe;
+        let x = prime_power_log(
+            &g.modpow(&c, p),
+            &g_inv.modpow(&c, p),
+            &h.modpow(&c, p),
+            &q,
+            e,
+            p,
+        )?;
+        residues.push(Int(x.into()));
+        moduli.push(Int(qe.into()));
+    }
+
+    let (x, _) = crt(&residues, &moduli).expect("coprime moduli");
+    // (ℤ/pℤ)* needn't be cyclic, so h may be out of reach of g after all
+    (g.modpow(x.0.magnitude(), p) == h).then_some(x)
+}
+
+/// Returns the `x < qᵉ` with `gˣ ≡ h (mod p)` for `g` of order `qᵉ` and its
+/// inverse `g_inv`, one base q digit at a time.
+fn prime_power_log(
+    g: &BigUint,
+    g_inv: &BigUint,
+    h: &BigUint,
+    q: &BigUint,
+    e: u32,
+    p: &BigUint,
+) -> Option<BigUint> {
+    // gamma generates the subgroup of order q
+    let gamma = g.modpow(&q.pow(e - 1), p);
+
+    let (mut x, mut qk) = (BigUint::zero(), BigUint::one());
+    for k in 0..e {
+        // (g^-x·h)^(q^(e-1-k)) = gamma^d for the k-th digit d of the logarithm
+        let t = (g_inv.modpow(&x, p) * h % p).modpow(&q.pow(e - 1 - k), p);
+        let d = prime_log(&gamma, &t, q, p)?;
+        x += d * &qk;
+        qk *= q;
+    }
+
+    Some(x)
+}
+
+/// Returns the `x < q` with `gˣ ≡ h (mod p)` for `g` of prime order `q`.
+fn prime_log(g: &BigUint, h: &BigUint, q: &BigUint, p: &BigUint) -> Option<BigUint> {
+    if !h.modpow(q, p).is_one() {
+        return None; // h isn't in the subgroup generated by g
+    }
+
+    if q.bits() <= BSGS_MAX_BITS {
+        bsgs(g, h, q.to_u64().expect("fit in u64"), p)
+    } else {
+        rho(g, h, q, p)
+    }
+}
+
+/// Returns the `x < q` with `gˣ ≡ h (mod p)` for `g` of order `q`, if any, by
+/// matching the giant steps `h·g^(-im)` against the baby steps `gʲ` for
+/// `i, j < m = ⌈√q⌉`.
+fn bsgs(g: &BigUint, h: &BigUint, q: u64, p: &BigUint) -> Option<BigUint> {
+    let m = isqrt(q - 1) + 1;
+
+    let mut baby = HashMap::with_capacity(m as usize);
+    let mut x = BigUint::one();
+    for j in 0..m {
+        baby.entry(x.clone()).or_insert(j);
+        x = x * g % p;
+    }
+
+    let giant = g.modpow(&BigUint::from((q - m % q) % q), p);
+    let mut y = h.clone();
+    for i in 0..m {
+        if let Some(&j) = baby.get(&y) {
+            return Some(BigUint::from((i * m + j) % q));
+        }
+        y = y * &giant % p;
+    }
+
+    None
+}
+
+/// Returns the `x < q` with `gˣ ≡ h (mod p)` for `g` of prime order `q` and `h`
+/// of order `q`, by Pollard's rho method. The walk through the `gᵃ·hᵇ`
+/// multiplies by one of a few random such powers, picked by the lowest bits, as
+/// proposed by Teske, until Brent's cycle detection finds two of them to be
+/// equal. It returns `None` if no walk leads to `x` within `WALK_STEPS·√q`
+/// steps, which is all but certain if `h` isn't a power of `g`.
+///
+/// E. Teske, "Speeding up Pollard's rho method for computing discrete
+/// logarithms", Algorithmic Number Theory (ANTS-III), LNCS 1423, 1998,
+/// pp. 541-554.
+fn rho(g: &BigUint, h: &BigUint, q: &BigUint, p: &BigUint) -> Option<BigUint> {
+    let mut rand = SplitMix64(h.iter_u64_digits().next().unwrap_or_default());
+    let mut point = || {
+        let (a, b) = (rand.below(q), rand.below(q));
+        (g.modpow(&a, p) * h.modpow(&b, p) % p, a, b)
+    };
+
+    let limit = q
+        .sqrt()
+        .to_u64()
+        .map_or(u64::MAX, |r| r.saturating_mul(WALK_STEPS));
+
+    'walk: for _ in 0..WALKS {
+        let steps: Vec<_> = (0..WALK_SIZE).map(|_| point()).collect();
+        let (mut x, mut a, mut b) = point();
+        let (mut y, mut ya, mut yb) = (x.clone(), a.clone(), b.clone());
+
+        let (mut power, mut lambda) = (1u64, 0u64);
+        for n in 0.. {
+            if n == limit {
+                continue 'walk;
+            }
+            if power == lambda {
+                (y, ya, yb) = (x.clone(), a.clone(), b.clone());
+                power *= 2;
+                lambda = 0;
+            }
+
+            let i = x.iter_u64_digits().next().unwrap_or_default() as usize % WALK_SIZE;
+            let (sx, sa, sb) = &steps[i];
+            x = x * sx % p;
+            a = (a + sa) % q;
+            b = (b + sb) % q;
+            lambda += 1;
+
+            if x == y {
+                break;
+            }
+        }
+
+        // gᵃ·hᵇ = g^ya·h^yb, so (b - yb)·x ≡ ya - a (mod q)
+        let db = (&b + q - &yb) % q;
+        if db.is_zero() {
+            continue; // a useless collision, start over from elsewhere
+        }
+        let da = (&ya + q - &a) % q;
+        return Some(da * db.modpow(&(q - 2u8), q) % q);
+    }
+
+    None
+}
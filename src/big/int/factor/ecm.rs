@@ -0,0 +1,228 @@
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer;
+use num_traits::One;
+
+use super::{mont::Montgomery, pm1::max_power};
+use crate::big::int::{prime::SplitMix64, Int};
+use crate::primes;
+
+// The number of stage 1 primes processed between two gcds.
+const BATCH: usize = 64;
+
+/// A point on a Montgomery curve `By² = x³ + Ax² + x` in projective `(X : Z)`
+/// coordinates, which leave out `y`.
+#[derive(Clone)]
+struct Point {
+    x: Vec<u64>,
+    z: Vec<u64>,
+}
+
+/// A Montgomery curve modulo `n`, given by `a24 = (A+2)/4`.
+struct Curve<'a> {
+    m: &'a Montgomery,
+    a24: Vec<u64>,
+}
+
+impl Curve<'_> {
+    /// Returns `P + Q`, given `P - Q`.
+    fn add(&self, p: &Point, q: &Point, diff: &Point) -> Point {
+        let m = self.m;
+        let u = m.mul(&m.sub(&p.x, &p.z), &m.add(&q.x, &q.z));
+        let v = m.mul(&m.add(&p.x, &p.z), &m.sub(&q.x, &q.z));
+        let s = m.add(&u, &v);
+        let d = m.sub(&u, &v);
+        Point {
+            x: m.mul(&diff.z, &m.mul(&s, &s)),
+            z: m.mul(&diff.x, &m.mul(&d, &d)),
+        }
+    }
+
+    /// Returns `2P`.
+    fn double(&self, p: &Point) -> Point {
+        let m = self.m;
+        let s = m.add(&p.x, &p.z);
+        let d = m.sub(&p.x, &p.z);
+        let s2 = m.mul(&s, &s);
+        let d2 = m.mul(&d, &d);
+        let t = m.sub(&s2, &d2);
+        Point {
+            x: m.mul(&s2, &d2),
+            z: m.mul(&t, &m.add(&d2, &m.mul(&self.a24, &t))),
+        }
+    }
+
+    /// Returns `kP` for `k` >= 1 by the Montgomery ladder.
+    fn mul(&self, p: &Point, k: u64) -> Point {
+        let (mut r0, mut r1) = (p.clone(), self.double(p));
+        for i in (0..63 - k.leading_zeros()).rev() {
+            if (k >> i) & 1 == 1 {
+                r0 = self.add(&r1, &r0, p);
+                r1 = self.double(&r1);
+            } else {
+                r1 = self.add(&r0, &r1, p);
+                r0 = self.double(&r0);
+            }
+        }
+        r0
+    }
+
+    /// Returns `X/Z` for the point `p`, or a factor of `n` if `Z` isn't invertible.
+    fn normalize(&self, p: &Point, n: &BigUint) -> Result<Vec<u64>, Option<BigUint>> {
+        let z = self.m.to_int(&p.z);
+        let mut inv = Int::default();
+        match inv.mod_inverse(&Int(BigInt::from(z.clone())), &Int(BigInt::from(n.clone()))) {
+            Some(inv) => Ok(self.m.mul(&p.x, &self.m.residue(inv.0.magnitude()))),
+            None => Err(proper_factor(&z, n)),
+        }
+    }
+}
+
+/// Looks for a proper factor of the odd `n`, which isn't a prime power, by
+/// Lenstra's elliptic curve method on up to `curves` curves. See [super::ecm].
+pub(super) fn ecm(n: &BigUint, b1: u64, b2: u64, curves: usize) -> Option<BigUint> {
+    let m = Montgomery::new(n);
+    let primes: Vec<u64> = primes::iter(..=b2.max(b1)).collect();
+    let (stage1, stage2) = primes.split_at(primes.partition_point(|&p| p <= b1));
+
+    let mut rand = SplitMix64(n.iter_u64_digits().next().unwrap_or_default());
+    for _ in 0..curves {
+        // σ in [6, 2³²)
+        let sigma = 6 + rand.next_u64() % ((1 << 32) - 6);
+        if let Some(d) = run_curve(n, &m, sigma, b1, stage1, stage2) {
+            return Some(d);
+        }
+    }
+
+    None
+}
+
+/// Runs both stages of ECM on the curve of Suyama's parametrization with `σ`.
+fn run_curve(
+    n: &BigUint,
+    m: &Montgomery,
+    sigma: u64,
+    b1: u64,
+    stage1: &[u64],
+    stage2: &[u64],
+) -> Option<BigUint> {
+    // u = σ²-5, v = 4σ, P = (u³ : v³) and (A+2)/4 = (v-u)³(3u+v) / 16u³v
+    let three = BigUint::from(3u8);
+    let u = (BigUint::from(sigma) * sigma - 5u8) % n;
+    let v = BigUint::from(4 * sigma) % n;
+    let (u3, v3) = (u.modpow(&three, n), v.modpow(&three, n));
+
+    let den = &u3 * &v * 16u8 % n;
+    let mut inv = Int::default();
+    if inv
+        .mod_inverse(
+            &Int(BigInt::from(den.clone())),
+            &Int(BigInt::from(n.clone())),
+        )
+        .is_none()
+    {
+        return proper_factor(&den, n);
+    }
+
+    let num = (&v + n - &u).modpow(&three, n) * (&u * 3u8 + &v) % n;
+    let curve = Curve {
+        m,
+        a24: m.residue(&(num * inv.0.magnitude())),
+    };
+    let mut q = Point {
+        x: m.residue(&u3),
+        z: m.residue(&v3),
+    };
+
+    // stage 1: Q = kP, where k is the product of all prime powers <= b1. If all
+    // factors of n show up at once in a batch, it is retried one prime at a time.
+    for chunk in stage1.chunks(BATCH) {
+        let saved = q.clone();
+        for &p in chunk {
+            q = curve.mul(&q, max_power(p, b1));
+        }
+
+        let g = m.to_int(&q.z).gcd(n);
+        if g.is_one() {
+            continue;
+        } else if g != *n {
+            return Some(g);
+        }
+
+        q = saved;
+        for &p in chunk {
+            q = curve.mul(&q, max_power(p, b1));
+            let d = proper_factor(&m.to_int(&q.z), n);
+            if d.is_some() {
+                return d;
+            }
+        }
+        return None;
+    }
+
+    stage_2(n, &curve, &q, stage2)
+}
+
+/// Looks for a prime p in `primes` with `pQ = O` modulo a factor of `n`, by the
+/// baby-step giant-step continuation: each p is written as `p = kD ± j` with
+/// `j < D/2` coprime to `D`, and `kDQ = ±jQ` shows as a common factor of `n`
+/// and the cross product of the x-coordinates of `kDQ` and `jQ`. Primes below
+/// `D/2` are skipped.
+fn stage_2(n: &BigUint, curve: &Curve, q: &Point, primes: &[u64]) -> Option<BigUint> {
+    let &last = primes.last()?;
+    let m = curve.m;
+    let d: u64 = if last < 100_000 { 210 } else { 2310 };
+
+    // baby steps: the normalized x-coordinates of jQ for odd j < D/2, using
+    // (j+2)Q = jQ + 2Q with the difference (j-2)Q
+    let mut baby = vec![Vec::new(); (d / 2 + 1) as usize];
+    baby[1] = match curve.normalize(q, n) {
+        Ok(x) => x,
+        Err(d) => return d,
+    };
+
+    let q2 = curve.double(q);
+    let (mut prev, mut cur) = (q.clone(), curve.add(&q2, q, q));
+    for j in (3..d / 2).step_by(2) {
+        if j.gcd(&d) == 1 {
+            baby[j as usize] = match curve.normalize(&cur, n) {
+                Ok(x) => x,
+                Err(d) => return d,
+            };
+        }
+        let next = curve.add(&cur, &q2, &prev);
+        prev = std::mem::replace(&mut cur, next);
+    }
+
+    // giant steps: kDQ and (k+1)DQ for consecutive k, using
+    // (k+2)DQ = (k+1)DQ + DQ with the difference kDQ
+    let g = curve.mul(q, d);
+    let mut k = ((primes[0] + d / 2) / d).max(1);
+    let (mut gk, mut gk1) = (curve.mul(&g, k), curve.mul(&g, k + 1));
+
+    let mut acc = m.one();
+    for &p in primes {
+        let pk = (p + d / 2) / d;
+        if pk < k {
+            continue;
+        }
+        while k < pk {
+            let next = curve.add(&gk1, &g, &gk);
+            gk = std::mem::replace(&mut gk1, next);
+            k += 1;
+        }
+
+        // baby[j] is empty iff p divides D
+        let j = p.abs_diff(k * d) as usize;
+        if !baby[j].is_empty() {
+            acc = m.mul(&acc, &m.sub(&gk.x, &m.mul(&baby[j], &gk.z)));
+        }
+    }
+
+    proper_factor(&m.to_int(&acc), n)
+}
+
+/// Returns gcd(x, n) if it's a proper factor of `n`.
+fn proper_factor(x: &BigUint, n: &BigUint) -> Option<BigUint> {
+    let g = x.gcd(n);
+    (!g.is_one() && g != *n).then_some(g)
+}
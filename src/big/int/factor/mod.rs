@@ -0,0 +1,366 @@
+//! Integer factorization.
+//!
+//! [factorize] splits an [Int] into its prime factors by a pipeline of
+//!
+//! - trial division by the primes below a bound,
+//! - a perfect power check,
+//! - Pollard's p-1 method,
+//! - Brent's variant of Pollard's rho method,
+//! - the self-initializing quadratic sieve ([siqs]) for composites of 20 to 100
+//!   digits, after a round of ECM for those above 50 digits,
+//! - Lenstra's elliptic curve method ([ecm]) with growing bounds,
+//!
+//! where every cofactor is tested by [Int::probably_prime] before it is split
+//! any further. [Factorizer] configures the bounds of the individual methods.
+//!
+//! Rho finds prime factors of up to 10 digits or so in milliseconds, and ECM
+//! those of 20 digits within seconds. Its running time grows with the size of
+//! the smallest factor rather than that of `n`, whereas SIQS only depends on
+//! the size of `n`: it splits 50 digits in well under a second, 60 digits in a
+//! few seconds and 70 digits in about a minute.
+
+use std::collections::BTreeMap;
+
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+
+use super::{root::perfect_power, Int};
+use crate::primes;
+
+mod ecm;
+mod mont;
+mod pm1;
+mod rho;
+mod siqs;
+
+// SIQS is only used for composites of up to this many digits.
+const SIQS_MAX_DIGITS: u32 = 100;
+
+// Above this many digits, a round of ECM goes before SIQS. It costs a fraction
+// of the sieve and finds factors of 15 digits or so.
+const SIQS_ECM_DIGITS: u32 = 50;
+
+// Trial division bounds up to this one share the primes sieved once.
+const SMALL_PRIMES_BOUND: u64 = 1 << 16;
+
+lazy_static::lazy_static! {
+    static ref SMALL_PRIMES: Vec<u64> = primes::iter(..SMALL_PRIMES_BOUND).collect();
+}
+
+/// Looks for a proper factor of `|n|` by Lenstra's elliptic curve method, running
+/// both stages on up to `curves` curves. It returns `None` if no factor is found,
+/// which is always the case for primes, 0 and ±1.
+///
+/// Stage 1 multiplies a point on a random Montgomery curve of Suyama's
+/// parametrization by all prime powers `≤ b1` and stage 2 looks for one more
+/// prime in `(b1, b2]` by baby steps and giant steps. A factor p is found if
+/// the order of the curve modulo p is made up of these primes. Typical choices
+/// are `b2 = 100·b1` and
+/// ```ignore
+/// factor digits   b1          curves
+/// 15              2000        25
+/// 20              11000       90
+/// 25              50000       300
+/// 30              250000      700
+/// ```
+///
+/// H. W. Lenstra Jr., "Factoring integers with elliptic curves", Annals of
+/// Mathematics 126(3), 1987, pp. 649-673.
+///
+/// P. L. Montgomery, "Speeding the Pollard and elliptic curve methods of
+/// factorization", Mathematics of Computation 48(177), 1987, pp. 243-264.
+pub fn ecm(n: &Int, b1: u64, b2: u64, curves: usize) -> Option<Int> {
+    let n = n.0.magnitude();
+    if n.bits() <= 2 {
+        return None;
+    }
+
+    let d = if n.is_even() {
+        Some(BigUint::from(2u8))
+    } else if let Some((b, _)) = perfect_power(n) {
+        Some(b)
+    } else {
+        ecm::ecm(n, b1, b2, curves)
+    };
+
+    d.map(|d| Int(BigInt::from(d)))
+}
+
+/// Returns the prime factorization of `n` with the default [Factorizer], see
+/// [Factorizer::factor].
+pub fn factorize(n: &Int) -> Vec<(Int, u32)> {
+    Factorizer::new().factor(n)
+}
+
+/// Looks for a proper factor of `|n|` by the self-initializing quadratic sieve.
+/// It returns `None` if no factor is found, which is always the case for primes,
+/// 0 and ±1.
+///
+/// Unlike [ecm], its running time only depends on the size of `n`, which makes
+/// it the method of choice for products of two primes of about the same size.
+/// The sieve collects relations `(ax+b)² ≡ ∏ pᵉ (mod kn)` over a factor base of
+/// small primes for a Knuth-Schroeppel multiplier k, switching between the
+/// polynomials of an `a` cheaply, and pairs up those with one large prime left.
+/// Gaussian elimination modulo 2 then combines them into congruences of squares
+/// `x² ≡ y² (mod n)`, and gcd(x-y, n) is a proper factor with probability ½ for
+/// each of them.
+///
+/// C. Pomerance, "The quadratic sieve factoring algorithm", EUROCRYPT '84,
+/// LNCS 209, 1985, pp. 169-182.
+///
+/// S. P. Contini, "Factoring integers with the self-initializing quadratic
+/// sieve", Master's thesis, University of Georgia, 1997.
+pub fn siqs(n: &Int) -> Option<Int> {
+    let n = n.0.magnitude();
+    if n.bits() <= 2 {
+        return None;
+    }
+
+    let d = if n.is_even() {
+        Some(BigUint::from(2u8))
+    } else if let Some((b, _)) = perfect_power(n) {
+        Some(b)
+    } else if Int(BigInt::from(n.clone())).probably_prime(20) {
+        None
+    } else {
+        siqs::siqs(n)
+    };
+
+    d.map(|d| Int(BigInt::from(d)))
+}
+
+/// A Factorizer computes prime factorizations of [Int]s, with configurable bounds
+/// for the methods of the pipeline described in the [module documentation](self).
+#[derive(Debug, Clone)]
+pub struct Factorizer {
+    trial_bound: u64,
+    pm1_b1: u64,
+    pm1_b2: u64,
+    rho_iterations: u64,
+    ecm_b1: u64,
+    ecm_b2: u64,
+    ecm_curves: usize,
+    siqs_digits: u32,
+    reps: usize,
+}
+
+impl Default for Factorizer {
+    fn default() -> Self {
+        Self {
+            trial_bound: SMALL_PRIMES_BOUND,
+            pm1_b1: 10_000,
+            pm1_b2: 1_000_000,
+            rho_iterations: 1 << 16,
+            ecm_b1: 2_000,
+            ecm_b2: 200_000,
+            ecm_curves: 25,
+            siqs_digits: 20,
+            reps: 20,
+        }
+    }
+}
+
+impl Factorizer {
+    /// Returns a Factorizer with the default bounds: trial division by the primes
+    /// below 2¹⁶, p-1 with B1 = 10⁴ and B2 = 10⁶, 2¹⁶ rho iterations, SIQS from
+    /// 20 digits on, and 25 ECM curves with B1 = 2000 and B2 = 2·10⁵ at first.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the prime factorization of `n` as pairs `(p, e)` of the primes `p`
+    /// dividing `n` in increasing order, and their multiplicities `e`. A negative
+    /// `n` is led by `(-1, 1)`, and 1 has no prime factors.
+    ///
+    /// The factors are checked by [Int::probably_prime] with the configured number
+    /// of rounds. Each time ECM runs out of curves, it starts over with five times
+    /// the bounds and three times the curves, so `factor` only returns once `n` is
+    /// fully factored. With ECM turned off, the rho method restarts with another
+    /// polynomial and twice the iterations instead.
+    ///
+    /// # Panics
+    /// If `n` is 0.
+    pub fn factor(&self, n: &Int) -> Vec<(Int, u32)> {
+        assert!(!n.0.is_zero(), "cannot factor 0");
+
+        let mut factors = BTreeMap::new();
+        let m = self.trial_division(n.0.magnitude().clone(), &mut factors);
+
+        // the primes for p-1 are only sieved once they are needed
+        let mut pm1_primes: Option<Vec<u64>> = None;
+        let mut todo = vec![(m, 1)];
+        while let Some((m, e)) = todo.pop() {
+            if m.is_one() {
+                continue;
+            }
+            if self.is_prime(&m) {
+                *factors.entry(m).or_insert(0) += e;
+                continue;
+            }
+            if let Some((b, k)) = perfect_power(&m) {
+                todo.push((b, e * k));
+                continue;
+            }
+
+            let primes = pm1_primes.get_or_insert_with(|| primes::iter(..=self.pm1_b2).collect());
+            let d = self.split(&m, primes);
+            let cofactor = &m / &d;
+            todo.push((d, e));
+            todo.push((cofactor, e));
+        }
+
+        let mut out = Vec::with_capacity(factors.len() + 1);
+        if n.0.is_negative() {
+            out.push((Int::new(-1), 1));
+        }
+        out.extend(factors.into_iter().map(|(p, e)| (Int(BigInt::from(p)), e)));
+
+        out
+    }
+
+    /// Sets the bounds of the first round of ECM and returns `self`, see [ecm].
+    pub fn set_ecm_bounds(&mut self, b1: u64, b2: u64) -> &mut Self {
+        self.ecm_b1 = b1.max(1);
+        self.ecm_b2 = b2.max(self.ecm_b1);
+        self
+    }
+
+    /// Sets the number of curves of the first round of ECM and returns `self`.
+    /// ECM is skipped for 0 curves.
+    pub fn set_ecm_curves(&mut self, curves: usize) -> &mut Self {
+        self.ecm_curves = curves;
+        self
+    }
+
+    /// Sets the bounds of Pollard's p-1 method and returns `self`. Stage 1 uses
+    /// the prime powers up to `b1`, and stage 2 the primes in `(b1, b2]`. The
+    /// method is skipped for `b1` = 0.
+    pub fn set_pm1_bounds(&mut self, b1: u64, b2: u64) -> &mut Self {
+        self.pm1_b1 = b1;
+        self.pm1_b2 = b2.max(b1);
+        self
+    }
+
+    /// Sets the number of Miller-Rabin rounds passed to [Int::probably_prime] and
+    /// returns `self`.
+    pub fn set_reps(&mut self, reps: usize) -> &mut Self {
+        self.reps = reps;
+        self
+    }
+
+    /// Sets the number of iterations of the rho method before SIQS or ECM takes
+    /// over and returns `self`.
+    pub fn set_rho_iterations(&mut self, iterations: u64) -> &mut Self {
+        self.rho_iterations = iterations.max(1);
+        self
+    }
+
+    /// Sets the number of decimal digits from which on composites are split by
+    /// [siqs] and returns `self`. SIQS is skipped for 0.
+    pub fn set_siqs_digits(&mut self, digits: u32) -> &mut Self {
+        self.siqs_digits = digits;
+        self
+    }
+
+    /// Sets the bound of trial division and returns `self`. Trial division by the
+    /// primes below `bound` is cheaper than any other method for small factors.
+    pub fn set_trial_bound(&mut self, bound: u64) -> &mut Self {
+        self.trial_bound = bound;
+        self
+    }
+
+    fn is_prime(&self, n: &BigUint) -> bool {
+        Int(BigInt::from(n.clone())).probably_prime(self.reps)
+    }
+
+    /// Returns a proper factor of the composite `n`, which isn't a perfect power.
+    fn split(&self, n: &BigUint, primes: &[u64]) -> BigUint {
+        if n.is_even() {
+            return BigUint::from(2u8);
+        }
+
+        if self.pm1_b1 > 0 {
+            if let Some(d) = pm1::pm1(n, self.pm1_b1, self.pm1_b2, primes) {
+                return d;
+            }
+        }
+
+        if let Some(d) = rho::rho(n, 1, self.rho_iterations) {
+            return d;
+        }
+
+        let digits = (n.bits() as f64 * std::f64::consts::LOG10_2).ceil() as u32;
+        if self.siqs_digits > 0 && (self.siqs_digits..=SIQS_MAX_DIGITS).contains(&digits) {
+            if digits > SIQS_ECM_DIGITS && self.ecm_curves > 0 {
+                if let Some(d) = ecm::ecm(n, self.ecm_b1, self.ecm_b2, self.ecm_curves) {
+                    return d;
+                }
+            }
+            if let Some(d) = siqs::siqs(n) {
+                return d;
+            }
+        }
+
+        if self.ecm_curves > 0 {
+            let (mut b1, mut b2, mut curves) = (self.ecm_b1, self.ecm_b2, self.ecm_curves);
+            loop {
+                if let Some(d) = ecm::ecm(n, b1, b2, curves) {
+                    return d;
+                }
+                b1 = b1.saturating_mul(5);
+                b2 = b2.saturating_mul(5);
+                curves = curves.saturating_mul(3);
+            }
+        }
+
+        let mut iterations = self.rho_iterations;
+        for c in 2.. {
+            iterations = iterations.saturating_mul(2);
+            if let Some(d) = rho::rho(n, c, iterations) {
+                return d;
+            }
+        }
+
+        unreachable!("rho ran out of polynomials")
+    }
+
+    /// Divides the primes below the trial bound out of `n`, adds them to `factors`
+    /// and returns the cofactor.
+    fn trial_division(&self, mut n: BigUint, factors: &mut BTreeMap<BigUint, u32>) -> BigUint {
+        let sieved;
+        let small_primes = if self.trial_bound <= SMALL_PRIMES_BOUND {
+            &SMALL_PRIMES[..SMALL_PRIMES.partition_point(|&p| p < self.trial_bound)]
+        } else {
+            sieved = primes::iter(..self.trial_bound).collect::<Vec<_>>();
+            &sieved[..]
+        };
+
+        for &p in small_primes {
+            if n.bits() <= 64 {
+                let v = n.to_u64().expect("fit in u64");
+                if p.saturating_mul(p) > v {
+                    break;
+                }
+                if !v.is_multiple_of(p) {
+                    continue;
+                }
+            } else if !(&n % p).is_zero() {
+                continue;
+            }
+
+            let mut e = 0;
+            loop {
+                let (q, r) = n.div_rem(&BigUint::from(p));
+                if !r.is_zero() {
+                    break;
+                }
+                n = q;
+                e += 1;
+            }
+            factors.insert(BigUint::from(p), e);
+        }
+
+        n
+    }
+}
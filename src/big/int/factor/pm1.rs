@@ -0,0 +1,106 @@
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::{One, Zero};
+
+// The number of primes processed between two gcds.
+const BATCH: usize = 64;
+
+/// Looks for a proper factor of the odd composite `n` by Pollard's p-1 method,
+/// which finds prime factors p for which p-1 is a product of prime powers `≤ b1`
+/// and at most one more prime `≤ b2`. `primes` must include all primes `≤ b2`.
+/// It returns `None` if no factor is found.
+///
+/// J. M. Pollard, "Theorems on factorization and primality testing",
+/// Proc. Cambridge Philos. Soc. 76 (1974), pp. 521-528.
+pub(super) fn pm1(n: &BigUint, b1: u64, b2: u64, primes: &[u64]) -> Option<BigUint> {
+    // stage 1: a = 2^E mod n, where E is the product of all prime powers <= b1
+    let mut a = BigUint::from(2u8);
+    let stage1 = &primes[..primes.partition_point(|&p| p <= b1)];
+    for chunk in stage1.chunks(BATCH) {
+        let saved = a.clone();
+        for &p in chunk {
+            a = a.modpow(&BigUint::from(max_power(p, b1)), n);
+        }
+
+        match gcd_minus_one(&a, n) {
+            g if g.is_one() => continue,
+            g if &g != n => return Some(g),
+            // several factors were found at once, so retry the chunk one prime
+            // power at a time
+            _ => {
+                a = saved;
+                for &p in chunk {
+                    let mut pk = p;
+                    while pk <= b1 {
+                        a = a.modpow(&BigUint::from(p), n);
+                        let g = gcd_minus_one(&a, n);
+                        if &g == n {
+                            return None;
+                        } else if !g.is_one() {
+                            return Some(g);
+                        }
+                        pk = pk.saturating_mul(p);
+                    }
+                }
+                return None;
+            }
+        }
+    }
+
+    // stage 2: accumulate the products of a^q - 1 for the primes b1 < q <= b2,
+    // stepping from one q to the next by a table of a^d for the even gaps d
+    let stage2 = &primes[stage1.len()..primes.partition_point(|&p| p <= b2)];
+    let &first = stage2.first()?;
+
+    let a2 = &a * &a % n;
+    let mut steps = vec![a2.clone()];
+    let mut aq = a.modpow(&BigUint::from(first), n);
+    let mut acc = BigUint::one();
+    let mut prev = first;
+    for (i, &q) in stage2.iter().enumerate() {
+        let d = ((q - prev) / 2) as usize;
+        if d > 0 {
+            while steps.len() < d {
+                let next = steps.last().expect("a²") * &a2 % n;
+                steps.push(next);
+            }
+            aq = aq * &steps[d - 1] % n;
+        }
+        prev = q;
+
+        acc = acc * minus_one(&aq, n) % n;
+        if (i + 1) % (4 * BATCH) == 0 || i + 1 == stage2.len() {
+            let g = acc.gcd(n);
+            if &g == n {
+                return None;
+            } else if !g.is_one() {
+                return Some(g);
+            }
+        }
+    }
+
+    None
+}
+
+/// Returns gcd(a-1, n) for `a` in [0, n).
+fn gcd_minus_one(a: &BigUint, n: &BigUint) -> BigUint {
+    minus_one(a, n).gcd(n)
+}
+
+/// Returns the largest power of `p` not above `bound`, for `p <= bound`.
+pub(super) fn max_power(p: u64, bound: u64) -> u64 {
+    let mut pk = p;
+    while let Some(v) = pk.checked_mul(p).filter(|&v| v <= bound) {
+        pk = v;
+    }
+    pk
+}
+
+/// Returns (a-1) mod n for `a` in [0, n).
+fn minus_one(a: &BigUint, n: &BigUint) -> BigUint {
+    if a.is_zero() {
+        n - 1u8
+    } else {
+        a - 1u8
+    }
+}
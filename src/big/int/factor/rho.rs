@@ -0,0 +1,107 @@
+use num_bigint::BigUint;
+use num_integer::Integer;
+use num_traits::{One, ToPrimitive};
+
+// The number of products |x-y| accumulated between two gcds.
+const BATCH: u64 = 128;
+
+/// Looks for a proper factor of the odd composite `n` by Brent's variant of
+/// Pollard's rho method, iterating `x ↦ x² + c (mod n)` about `max_iter` times
+/// at most. It returns `None` if no factor is found, which happens when the
+/// budget is exhausted or the cycles modulo all prime factors close at once.
+///
+/// R. P. Brent, "An improved Monte Carlo factorization algorithm",
+/// BIT 20 (1980), pp. 176-184.
+pub(super) fn rho(n: &BigUint, c: u64, max_iter: u64) -> Option<BigUint> {
+    if let Some(n) = n.to_u64() {
+        return rho_u64(n, c, max_iter).map(BigUint::from);
+    }
+
+    let c = BigUint::from(c);
+    let f = |x: &BigUint| (x * x + &c) % n;
+    let diff = |x: &BigUint, y: &BigUint| if x > y { x - y } else { y - x };
+
+    let (mut x, mut y, mut ys) = (BigUint::from(2u8), BigUint::from(2u8), BigUint::default());
+    let (mut q, mut g) = (BigUint::one(), BigUint::one());
+    let mut r = 1u64;
+    while g.is_one() {
+        // x stays at y_(r-1) while y walks the next r steps
+        x.clone_from(&y);
+        for _ in 0..r {
+            y = f(&y);
+        }
+
+        let mut k = 0;
+        while k < r && g.is_one() {
+            ys.clone_from(&y);
+            for _ in 0..BATCH.min(r - k) {
+                y = f(&y);
+                q = q * diff(&x, &y) % n;
+            }
+            g = q.gcd(n);
+            k += BATCH;
+        }
+
+        if r > max_iter {
+            break;
+        }
+        r *= 2;
+    }
+
+    if &g == n {
+        // the batch collapsed several factors, so redo it one step at a time
+        loop {
+            ys = f(&ys);
+            g = diff(&x, &ys).gcd(n);
+            if !g.is_one() {
+                break;
+            }
+        }
+    }
+
+    (!g.is_one() && &g != n).then_some(g)
+}
+
+/// [rho] for `n` < 2⁶⁴.
+fn rho_u64(n: u64, c: u64, max_iter: u64) -> Option<u64> {
+    let mul = |x: u64, y: u64| (x as u128 * y as u128 % n as u128) as u64;
+    let f = |x: u64| ((mul(x, x) as u128 + c as u128) % n as u128) as u64;
+
+    let (mut x, mut y, mut ys) = (2 % n, 2 % n, 0);
+    let (mut q, mut g) = (1, 1);
+    let mut r = 1u64;
+    while g == 1 {
+        x = y;
+        for _ in 0..r {
+            y = f(y);
+        }
+
+        let mut k = 0;
+        while k < r && g == 1 {
+            ys = y;
+            for _ in 0..BATCH.min(r - k) {
+                y = f(y);
+                q = mul(q, x.abs_diff(y));
+            }
+            g = q.gcd(&n);
+            k += BATCH;
+        }
+
+        if r > max_iter {
+            break;
+        }
+        r *= 2;
+    }
+
+    if g == n {
+        loop {
+            ys = f(ys);
+            g = x.abs_diff(ys).gcd(&n);
+            if g != 1 {
+                break;
+            }
+        }
+    }
+
+    (g != 1 && g != n).then_some(g)
+}
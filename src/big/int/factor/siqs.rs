@@ -0,0 +1,633 @@
+use std::collections::{hash_map::Entry, HashMap, HashSet};
+
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+
+use crate::big::int::{jacobi, prime::SplitMix64, Int};
+use crate::primes;
+
+// The sieve interval is made up of this many bytes on either side of 0 for
+// every block of the parameters.
+const BLOCK_SIZE: usize = 1 << 15;
+
+// The number of relations collected beyond the size of the factor base. Each of
+// them makes for another dependency.
+const EXTRA_RELATIONS: usize = 32;
+
+// Large primes up to this multiple of the largest prime of the factor base are
+// kept for the large prime variation.
+const LARGE_PRIME_MULTIPLIER: u64 = 64;
+
+// The odd squarefree multipliers k tried for kn.
+const MULTIPLIERS: [u64; 33] = [
+    1, 3, 5, 7, 11, 13, 15, 17, 19, 21, 23, 29, 31, 33, 35, 37, 39, 41, 43, 47, 51, 53, 55, 57, 59,
+    61, 65, 67, 69, 71, 73, 77, 79,
+];
+
+// (digits of n, size of the factor base, blocks of the sieve interval, slack of
+// the threshold in bits), which are interpolated linearly for the digits in
+// between
+const PARAMETERS: [[f64; 4]; 11] = [
+    [20.0, 80.0, 1.0, 4.0],
+    [25.0, 120.0, 1.0, 5.0],
+    [30.0, 200.0, 1.0, 6.0],
+    [35.0, 350.0, 1.0, 7.0],
+    [40.0, 600.0, 1.0, 9.0],
+    [45.0, 1000.0, 1.0, 11.0],
+    [50.0, 1500.0, 1.0, 14.0],
+    [60.0, 4000.0, 2.0, 20.0],
+    [70.0, 8000.0, 2.0, 20.0],
+    [80.0, 16000.0, 3.0, 24.0],
+    [90.0, 32000.0, 4.0, 28.0],
+];
+
+// Primes below this bound aren't sieved with, but only tried on the candidates.
+const SIEVE_BOUND: u32 = 256;
+
+/// A prime `p` of the factor base with a square root of kn modulo `p`, which is
+/// 0 for the primes dividing the multiplier k.
+struct Prime {
+    p: u32,
+    sqrt: u32,
+    log: u8,
+}
+
+impl Prime {
+    fn sieved(&self) -> bool {
+        self.p >= SIEVE_BOUND && self.sqrt != 0
+    }
+}
+
+/// A relation `y² ≡ large² · ∏ f (mod n)` over the factor base, where column 0
+/// of the `factors` stands for -1 and column `i+1` for the `i`-th prime.
+struct Relation {
+    y: BigUint,
+    factors: Vec<usize>,
+    large: u64,
+}
+
+/// Looks for a proper factor of the odd composite `n`, which isn't a perfect
+/// power, by the self-initializing quadratic sieve. See [super::siqs].
+pub(super) fn siqs(n: &BigUint) -> Option<BigUint> {
+    let digits = n.bits() as f64 * std::f64::consts::LOG10_2;
+    let (fb_size, blocks, slack) = parameters(digits);
+    let kn = n * multiplier(n);
+
+    let fb = match factor_base(n, &kn, fb_size) {
+        Ok(fb) => fb,
+        Err(d) => return Some(d),
+    };
+
+    let relations = Sieve::new(n, &kn, &fb, blocks * BLOCK_SIZE, slack).relations()?;
+    let columns = fb.len() + 1;
+    for dependency in dependencies(&relations, columns) {
+        // x² ≡ y² (mod n) from the product of the relations
+        let (mut x, mut y) = (BigUint::one(), BigUint::one());
+        let mut exponents = vec![0u32; columns];
+        for &i in &dependency {
+            let r = &relations[i];
+            x = x * &r.y % n;
+            y = y * r.large % n;
+            for &f in &r.factors {
+                exponents[f] += 1;
+            }
+        }
+        for (prime, &e) in fb.iter().zip(&exponents[1..]) {
+            debug_assert!(e % 2 == 0, "odd exponent in a dependency");
+            if e > 0 {
+                y = y * BigUint::from(prime.p).modpow(&BigUint::from(e / 2), n) % n;
+            }
+        }
+
+        let g = (x + n - y).gcd(n);
+        if !g.is_one() && g != *n {
+            return Some(g);
+        }
+    }
+
+    None
+}
+
+/// Returns the dependencies among the `relations` modulo 2, as sets of indices
+/// of relations whose product is a square. Relations which contain a column
+/// that no other relation contains are filtered out repeatedly first, and the
+/// remaining matrix is reduced by Gaussian elimination.
+fn dependencies(relations: &[Relation], columns: usize) -> Vec<Vec<usize>> {
+    let odd: Vec<Vec<usize>> = relations
+        .iter()
+        .map(|r| {
+            let mut parity = HashSet::new();
+            for &f in &r.factors {
+                if !parity.remove(&f) {
+                    parity.insert(f);
+                }
+            }
+            let mut odd: Vec<usize> = parity.into_iter().collect();
+            odd.sort_unstable();
+            odd
+        })
+        .collect();
+
+    let mut rows: Vec<usize> = (0..relations.len()).collect();
+    let mut weights = vec![0usize; columns];
+    loop {
+        weights.iter_mut().for_each(|w| *w = 0);
+        for &r in &rows {
+            for &c in &odd[r] {
+                weights[c] += 1;
+            }
+        }
+
+        let before = rows.len();
+        rows.retain(|&r| odd[r].iter().all(|&c| weights[c] > 1));
+        if rows.len() == before {
+            break;
+        }
+    }
+
+    // dense columns for those left, followed by the identity, which records the
+    // relations combined into each row
+    let mut dense = vec![usize::MAX; columns];
+    let mut width = 0;
+    for (c, &w) in weights.iter().enumerate() {
+        if w > 0 {
+            dense[c] = width;
+            width += 1;
+        }
+    }
+    let words = width.div_ceil(64);
+    let total = words + rows.len().div_ceil(64);
+    let mut matrix: Vec<Vec<u64>> = rows
+        .iter()
+        .enumerate()
+        .map(|(i, &r)| {
+            let mut row = vec![0u64; total];
+            for &c in &odd[r] {
+                row[dense[c] / 64] |= 1 << (dense[c] % 64);
+            }
+            row[words + i / 64] |= 1 << (i % 64);
+            row
+        })
+        .collect();
+
+    let mut rank = 0;
+    for c in 0..width {
+        let (w, bit) = (c / 64, 1u64 << (c % 64));
+        let Some(pivot) = (rank..matrix.len()).find(|&r| matrix[r][w] & bit != 0) else {
+            continue;
+        };
+        matrix.swap(rank, pivot);
+
+        let (done, rest) = matrix.split_at_mut(rank + 1);
+        let pivot = &done[rank];
+        for row in rest.iter_mut().filter(|row| row[w] & bit != 0) {
+            for (x, &y) in row[w..].iter_mut().zip(&pivot[w..]) {
+                *x ^= y;
+            }
+        }
+        rank += 1;
+    }
+
+    matrix[rank..]
+        .iter()
+        .map(|row| {
+            (0..rows.len())
+                .filter(|&i| row[words + i / 64] & (1 << (i % 64)) != 0)
+                .map(|i| rows[i])
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns the factor base of `kn`: 2, the primes dividing k and the odd primes
+/// `p` for which kn is a quadratic residue, up to `size` primes in all. If one
+/// of these primes divides `n`, it is returned instead.
+fn factor_base(n: &BigUint, kn: &BigUint, size: usize) -> Result<Vec<Prime>, BigUint> {
+    let mut out = vec![Prime {
+        p: 2,
+        sqrt: 1,
+        log: 1,
+    }];
+
+    let mut bound = (30 * size as u64).max(1000);
+    let mut last = 2;
+    while out.len() < size {
+        for p in primes::iter(last + 1..=bound) {
+            let r = (kn % p).to_u64().expect("below p");
+            if r == 0 {
+                if (n % p).is_zero() && *n != BigUint::from(p) {
+                    return Err(BigUint::from(p));
+                }
+            } else if jacobi(&Int::from(r), &Int::from(p)) != 1 {
+                continue;
+            }
+
+            let mut sqrt = Int::default();
+            sqrt.mod_sqrt(&Int::from(r), &Int::from(p))
+                .expect("kn is a square modulo p");
+            out.push(Prime {
+                p: p as u32,
+                sqrt: sqrt.0.to_u32().expect("below p"),
+                log: (p as f64).log2().round() as u8,
+            });
+            if out.len() == size {
+                break;
+            }
+        }
+        last = bound;
+        bound *= 2;
+    }
+
+    Ok(out)
+}
+
+/// Returns a⁻¹ mod m for `a` coprime to `m`.
+fn inv_mod(a: u64, m: u64) -> u64 {
+    let (mut r0, mut r1) = (m as i64, (a % m) as i64);
+    let (mut t0, mut t1) = (0i64, 1i64);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (t0, t1) = (t1, t0 - q * t1);
+    }
+    t0.rem_euclid(m as i64) as u64
+}
+
+/// Returns the Knuth-Schroeppel multiplier k for `n`, which maximizes the
+/// expected contribution of the small primes to kn - x².
+fn multiplier(n: &BigUint) -> u64 {
+    let small: Vec<u64> = primes::iter(..1000).collect();
+    let ln2 = std::f64::consts::LN_2;
+
+    let mut best = (f64::MIN, 1);
+    for k in MULTIPLIERS {
+        let kn = n * k;
+        let mut f = -0.5 * (k as f64).ln();
+        f += match (&kn % 8u8).to_u8() {
+            Some(1) => 2.0 * ln2,
+            Some(5) => ln2,
+            _ => 0.5 * ln2,
+        };
+        for &p in &small[1..] {
+            let r = (&kn % p).to_u64().expect("below p");
+            let ln = (p as f64).ln();
+            if r == 0 {
+                f += ln / p as f64;
+            } else if jacobi(&Int::from(r), &Int::from(p)) == 1 {
+                f += 2.0 * ln / (p - 1) as f64;
+            }
+        }
+
+        if f > best.0 {
+            best = (f, k);
+        }
+    }
+
+    best.1
+}
+
+/// Returns the size of the factor base, the number of blocks of the sieve
+/// interval and the slack of the threshold for an `n` of `digits` digits.
+fn parameters(digits: f64) -> (usize, usize, f64) {
+    let i = PARAMETERS.partition_point(|p| p[0] < digits);
+    let p = if i == 0 || i == PARAMETERS.len() {
+        PARAMETERS[i.min(PARAMETERS.len() - 1)]
+    } else {
+        let (lo, hi) = (PARAMETERS[i - 1], PARAMETERS[i]);
+        let t = (digits - lo[0]) / (hi[0] - lo[0]);
+        let mut p = lo;
+        for (p, (lo, hi)) in p.iter_mut().zip(lo.iter().zip(hi)) {
+            *p = lo + t * (hi - lo);
+        }
+        p
+    };
+
+    (p[1] as usize, p[2].round() as usize, p[3])
+}
+
+/// The state of sieving for relations with the polynomials
+/// `g(x) = ((ax+b)² - kn) / a` for `x` in `[-m, m)`.
+struct Sieve<'a> {
+    n: &'a BigUint,
+    kn: BigInt,
+    fb: &'a [Prime],
+    m: usize,
+    // the factor base indices a is chosen from, and how many of them
+    candidates: Vec<usize>,
+    s: usize,
+    // log₂ of the ideal a
+    target: f64,
+    threshold: u8,
+    large_bound: u64,
+    rand: SplitMix64,
+    used: HashSet<Vec<usize>>,
+    seen: HashSet<BigUint>,
+    sieve: Vec<u8>,
+    relations: Vec<Relation>,
+    partials: HashMap<u64, (BigUint, Vec<usize>)>,
+}
+
+impl<'a> Sieve<'a> {
+    fn new(n: &'a BigUint, kn: &BigUint, fb: &'a [Prime], m: usize, slack: f64) -> Self {
+        // a ≈ √(2kn) / m keeps |g(x)| below m√(kn/2) on the whole interval
+        let kn_bits = kn.bits() as f64;
+        let target = (kn_bits + 1.0) / 2.0 - (m as f64).log2();
+        let candidates: Vec<usize> = (0..fb.len()).filter(|&i| fb[i].sieved()).collect();
+        let log = |i: usize| (fb[candidates[i]].p as f64).log2();
+
+        // s primes of about 2000 each, as far as the factor base allows
+        let (min, max) = (log(0), log(candidates.len() - 1));
+        let mut s = ((target / 11.0).round() as usize).max(1);
+        while target / (s as f64) > max {
+            s += 1;
+        }
+        while s > 1 && target / (s as f64) < min {
+            s -= 1;
+        }
+
+        let largest = fb.last().expect("nonempty factor base").p as u64;
+        let large_bound = largest * LARGE_PRIME_MULTIPLIER;
+        let max_bits = (m as f64).log2() + kn_bits / 2.0 - 0.5;
+        let threshold = (max_bits - (large_bound as f64).log2() - slack).clamp(1.0, 128.0) as u8;
+
+        Self {
+            n,
+            kn: BigInt::from(kn.clone()),
+            fb,
+            m,
+            candidates,
+            s,
+            target,
+            threshold,
+            large_bound,
+            rand: SplitMix64(n.iter_u64_digits().next().unwrap_or_default()),
+            used: HashSet::new(),
+            seen: HashSet::new(),
+            sieve: vec![0; 2 * m],
+            relations: Vec::new(),
+            partials: HashMap::new(),
+        }
+    }
+
+    /// Adds the relation for `x` to the full or the partial relations, if `g(x)`
+    /// factors over the factor base apart from at most one large prime.
+    fn check(&mut self, x: i64, poly: &Poly, roots: &[(u32, u32)]) {
+        let x_big = BigInt::from(x);
+        let ax = &poly.a * &x_big;
+        let y = (&ax + &poly.b).magnitude() % self.n;
+        let g = (ax + &poly.b * 2u8) * &x_big + &poly.c;
+
+        let mut factors: Vec<usize> = poly.q.iter().map(|&i| i + 1).collect();
+        if g.is_negative() {
+            factors.push(0);
+        }
+        // the same y can turn up for several a
+        let mut g = g.magnitude().clone();
+        if g.is_zero() || self.seen.contains(&y) {
+            return;
+        }
+
+        let idx = (x + self.m as i64) as u32;
+        for (i, (prime, &(r1, r2))) in self.fb.iter().zip(roots).enumerate() {
+            let p = prime.p as u64;
+            let divides = if r1 == u32::MAX {
+                (&g % p).is_zero()
+            } else {
+                let r = idx % prime.p;
+                r == r1 || r == r2
+            };
+            if !divides {
+                continue;
+            }
+
+            let p = BigUint::from(p);
+            loop {
+                let (q, r) = g.div_rem(&p);
+                if !r.is_zero() {
+                    break;
+                }
+                g = q;
+                factors.push(i + 1);
+            }
+        }
+
+        if g >= BigUint::from(self.large_bound) {
+            return;
+        }
+        self.seen.insert(y.clone());
+        if g.is_one() {
+            self.relations.push(Relation {
+                y,
+                factors,
+                large: 1,
+            });
+        } else {
+            let large = g.to_u64().expect("below the large prime bound");
+            match self.partials.entry(large) {
+                Entry::Occupied(e) => {
+                    let (y2, f2) = e.get();
+                    factors.extend_from_slice(f2);
+                    self.relations.push(Relation {
+                        y: y * y2 % self.n,
+                        factors,
+                        large,
+                    });
+                }
+                Entry::Vacant(e) => {
+                    e.insert((y, factors));
+                }
+            }
+        }
+    }
+
+    /// Returns the factor base indices of the primes of a new `a`, or `None` if
+    /// no unused one turns up.
+    fn choose_a(&mut self) -> Option<Vec<usize>> {
+        let log = |i: usize| (self.fb[self.candidates[i]].p as f64).log2();
+        let ideal = self.target / self.s as f64;
+        let center = (0..self.candidates.len())
+            .find(|&i| log(i) >= ideal)
+            .unwrap_or(self.candidates.len() - 1);
+        let width = (2 * self.s).max(8);
+        let lo = center.saturating_sub(width);
+        let hi = (center + width).min(self.candidates.len());
+
+        for _ in 0..1000 {
+            // s-1 random primes around the ideal size, and the unused one that
+            // brings a closest to the target
+            let mut q = Vec::with_capacity(self.s);
+            let mut bits = 0.0;
+            while q.len() < (self.s - 1).min(hi - lo) {
+                let i = lo + (self.rand.next_u64() % (hi - lo) as u64) as usize;
+                if !q.contains(&i) {
+                    q.push(i);
+                    bits += log(i);
+                }
+            }
+
+            let rest = self.target - bits;
+            let mut last: Vec<usize> = (0..self.candidates.len())
+                .filter(|i| !q.contains(i))
+                .collect();
+            last.sort_by(|&i, &j| (log(i) - rest).abs().total_cmp(&(log(j) - rest).abs()));
+            for i in last {
+                let mut a: Vec<usize> = q.iter().chain([&i]).map(|&i| self.candidates[i]).collect();
+                a.sort_unstable();
+                if self.used.insert(a.clone()) {
+                    return Some(a);
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Collects one more relation than there are columns, and some more, or
+    /// returns `None` if the polynomials run out first.
+    fn relations(mut self) -> Option<Vec<Relation>> {
+        let needed = self.fb.len() + 1 + EXTRA_RELATIONS;
+        while self.relations.len() < needed {
+            let q = self.choose_a()?;
+            self.sieve_a(q, needed);
+        }
+
+        Some(self.relations)
+    }
+
+    /// Sieves with the 2^(s-1) polynomials for the `a` made up of the primes `q`,
+    /// stepping from one `b` to the next by a Gray code, until `needed` relations
+    /// are collected.
+    fn sieve_a(&mut self, q: Vec<usize>, needed: usize) {
+        let fb = self.fb;
+        let a = q.iter().fold(BigUint::one(), |a, &i| a * fb[i].p);
+
+        // B_j ≡ √kn (mod q_j) and B_j ≡ 0 (mod q_i) for i ≠ j, so that b = ∑ ±B_j
+        // meets b² ≡ kn (mod a) for all choices of signs
+        let big_b: Vec<BigUint> = q
+            .iter()
+            .map(|&i| {
+                let p = fb[i].p as u64;
+                let a_p = &a / p;
+                let inv = inv_mod((&a_p % p).to_u64().expect("below p"), p);
+                let mut gamma = fb[i].sqrt as u64 * inv % p;
+                if gamma > p / 2 {
+                    gamma = p - gamma;
+                }
+                a_p * gamma
+            })
+            .collect();
+        let mut b: BigInt = BigInt::from(big_b.iter().sum::<BigUint>());
+
+        // the roots x of g modulo the sieved primes as indices x+m into the sieve,
+        // and their steps 2·B_j/a
+        let mut roots = vec![(u32::MAX, u32::MAX); fb.len()];
+        let mut steps = vec![vec![0u32; fb.len()]; q.len()];
+        for (i, prime) in fb.iter().enumerate() {
+            if !prime.sieved() || q.contains(&i) {
+                continue;
+            }
+            let p = prime.p as u64;
+            let a_inv = inv_mod((&a % p).to_u64().expect("below p"), p);
+            let b_p = (b.magnitude() % p).to_u64().expect("below p");
+            let (t, m) = (prime.sqrt as u64, self.m as u64 % p);
+            roots[i] = (
+                ((a_inv * ((t + p - b_p) % p) + m) % p) as u32,
+                ((a_inv * ((2 * p - t - b_p) % p) + m) % p) as u32,
+            );
+            for (step, big_b) in steps.iter_mut().zip(&big_b) {
+                let b_p = (big_b % p).to_u64().expect("below p");
+                step[i] = (2 * b_p % p * a_inv % p) as u32;
+            }
+        }
+
+        let a = BigInt::from(a);
+        let mut positive = vec![true; q.len()];
+        for k in 0..1usize << (q.len() - 1) {
+            if k > 0 {
+                // b' = b ∓ 2B_j moves the roots by ±2B_j/a
+                let j = k.trailing_zeros() as usize + 1;
+                let two_b = BigInt::from(&big_b[j] * 2u8);
+                for (i, prime) in fb.iter().enumerate() {
+                    if roots[i].0 == u32::MAX {
+                        continue;
+                    }
+                    let p = prime.p;
+                    let step = if positive[j] {
+                        steps[j][i]
+                    } else {
+                        p - steps[j][i]
+                    };
+                    let shift = |r: u32| {
+                        if r >= p - step {
+                            r - (p - step)
+                        } else {
+                            r + step
+                        }
+                    };
+                    roots[i] = (shift(roots[i].0), shift(roots[i].1));
+                }
+                if positive[j] {
+                    b -= two_b;
+                } else {
+                    b += two_b;
+                }
+                positive[j] = !positive[j];
+            }
+
+            let c = (&b * &b - &self.kn) / &a;
+            let poly = Poly {
+                a: a.clone(),
+                b: b.clone(),
+                c,
+                q: &q,
+            };
+            self.sieve_poly(&poly, &roots);
+            if self.relations.len() >= needed {
+                return;
+            }
+        }
+    }
+
+    /// Sieves `g(x)` for `x` in `[-m, m)` with the roots of the sieved primes,
+    /// and checks the candidates above the threshold.
+    fn sieve_poly(&mut self, poly: &Poly, roots: &[(u32, u32)]) {
+        let m = self.m;
+        // the values start at 128 - threshold, so that the candidates are those
+        // with the high bit set, which are found eight at a time
+        self.sieve.fill(128 - self.threshold);
+
+        for (prime, &(r1, r2)) in self.fb.iter().zip(roots) {
+            if r1 == u32::MAX {
+                continue;
+            }
+            let (p, log) = (prime.p as usize, prime.log);
+            for r in [r1, r2] {
+                // the roots of primes above 2m may lie beyond the interval
+                let hits = self.sieve.get_mut(r as usize..).unwrap_or_default();
+                for v in hits.iter_mut().step_by(p) {
+                    *v = v.wrapping_add(log);
+                }
+            }
+        }
+
+        let mut candidates = Vec::new();
+        for (i, chunk) in self.sieve.chunks_exact(8).enumerate() {
+            let word = u64::from_le_bytes(chunk.try_into().expect("8 bytes"));
+            if word & 0x8080_8080_8080_8080 != 0 {
+                candidates.extend((8 * i..8 * i + 8).filter(|&j| self.sieve[j] >= 128));
+            }
+        }
+        for i in candidates {
+            self.check(i as i64 - m as i64, poly, roots);
+        }
+    }
+}
+
+/// A polynomial `g(x) = ax² + 2bx + c` with `a` the product of the primes `q`.
+struct Poly<'a> {
+    a: BigInt,
+    b: BigInt,
+    c: BigInt,
+    q: &'a [usize],
+}
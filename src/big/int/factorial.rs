@@ -0,0 +1,158 @@
+use num_bigint::BigUint;
+use num_traits::One;
+
+use super::Int;
+use crate::primes;
+
+// Products of at most this many words are accumulated one word at a time.
+const PRODUCT_LEAF_SIZE: usize = 16;
+
+impl Int {
+    /// Sets `self` to the double factorial `n!! = n·(n-2)·(n-4)···`, whose last
+    /// factor is 2 for even `n` and 1 for odd `n`, and returns `self`. `0!!` is 1.
+    pub fn double_factorial(&mut self, n: u64) -> &mut Self {
+        self.0 = double_factorial(n).into();
+        self
+    }
+
+    /// Sets `self` to `n! = 1·2···n` and returns `self`. `0!` is 1.
+    ///
+    /// The odd part of `n!` is built by Luschny's prime swing algorithm from the
+    /// prime factorizations of the swing numbers `k!/⌊k/2⌋!²`, and its factors
+    /// of 2 are shifted in at the end.
+    pub fn factorial(&mut self, n: u64) -> &mut Self {
+        self.0 = factorial(n).into();
+        self
+    }
+
+    /// Sets `self` to the primorial `n#`, the product of all primes `≤ n`, and
+    /// returns `self`. `0#` and `1#` are 1.
+    pub fn primorial(&mut self, n: u64) -> &mut Self {
+        self.0 = product(&primes::iter(..=n).collect::<Vec<_>>()).into();
+        self
+    }
+}
+
+/// Returns the binomial coefficient of (n, k) for 0 < k <= n - k.
+///
+/// The exponent of each prime p <= k in the result is the number of borrows
+/// when subtracting k from n in base p (Kummer's theorem). Dividing these primes
+/// out of n-k+1, ..., n leaves the part of the result made up of primes > k.
+pub(super) fn binomial(n: u64, k: u64) -> BigUint {
+    let lo = n - k + 1;
+    let mut factors: Vec<u64> = (lo..=n).collect();
+
+    for p in primes::iter(..=k) {
+        let start = ((p - lo % p) % p) as usize;
+        for f in factors[start..].iter_mut().step_by(p as usize) {
+            while *f % p == 0 {
+                *f /= p;
+            }
+        }
+
+        let (mut a, mut b, mut borrow) = (n, k, 0);
+        while a > 0 {
+            if a % p < b % p + borrow {
+                factors.push(p);
+                borrow = 1;
+            } else {
+                borrow = 0;
+            }
+            a /= p;
+            b /= p;
+        }
+    }
+
+    product(&factors)
+}
+
+/// Returns `n!!`, see [Int::double_factorial].
+fn double_factorial(n: u64) -> BigUint {
+    let m = n / 2;
+    if n.is_multiple_of(2) {
+        // (2m)!! = 2ᵐ·m!
+        return factorial(m) << m;
+    }
+
+    // (2m+1)! = (2m+1)!!·(2m)!!, so (2m+1)!! is the odd part of (2m+1)! divided
+    // by the odd part of m!, which leaves the odd part of the swing number
+    let primes: Vec<u64> = primes::iter(..=n).collect();
+    odd_factorial(m, &primes) * odd_swing(n, &primes)
+}
+
+/// Returns `n!`, see [Int::factorial].
+fn factorial(n: u64) -> BigUint {
+    // there are ⌊n/2⌋ + ⌊n/4⌋ + ... = n - popcount(n) factors of 2 in n!
+    odd_factorial(n, &primes::iter(..=n).collect::<Vec<_>>()) << (n - n.count_ones() as u64)
+}
+
+/// Returns the odd part of `n!`, using `(n!)' = (⌊n/2⌋!)'²·swing(n)'`, where
+/// `'` denotes the odd part. `primes` must include all primes <= `n`.
+fn odd_factorial(n: u64, primes: &[u64]) -> BigUint {
+    if n < 3 {
+        return BigUint::one();
+    }
+
+    let half = odd_factorial(n / 2, primes);
+    &half * &half * odd_swing(n, primes)
+}
+
+/// Returns the odd part of the swing number `n!/⌊n/2⌋!²`. `primes` must include
+/// all primes <= `n`.
+fn odd_swing(n: u64, primes: &[u64]) -> BigUint {
+    // the exponent of p in the swing number is the number of i >= 1 with
+    // ⌊n/pⁱ⌋ odd, which is at most 1 for p > √n
+    let mut factors = Vec::new();
+    for &p in primes.iter().skip(1).take_while(|&&p| p <= n) {
+        let mut q = n;
+        while q >= p {
+            q /= p;
+            if q & 1 == 1 {
+                factors.push(p);
+            }
+        }
+    }
+
+    product(&factors)
+}
+
+/// Returns the product of `factors`. Neighbouring factors are first packed into
+/// words as long as their product fits, and the words are then multiplied as a
+/// balanced product tree, so that the large multiplications have operands of
+/// similar sizes.
+pub(super) fn product(factors: &[u64]) -> BigUint {
+    let mut words = Vec::with_capacity(factors.len());
+    let mut acc = 1u64;
+    for &f in factors {
+        match acc.checked_mul(f) {
+            Some(v) => acc = v,
+            None => {
+                words.push(acc);
+                acc = f;
+            }
+        }
+    }
+    words.push(acc);
+
+    product_tree(&words)
+}
+
+fn product_tree(words: &[u64]) -> BigUint {
+    if words.len() <= PRODUCT_LEAF_SIZE {
+        return words.iter().fold(BigUint::one(), |acc, &w| acc * w);
+    }
+
+    let (lo, hi) = words.split_at(words.len() / 2);
+    product_tree(lo) * product_tree(hi)
+}
+
+/// Returns the product of all integers in [a, b] for 1 <= a <= b, split in
+/// halves recursively.
+pub(super) fn range_product(a: u64, b: u64) -> BigUint {
+    if b - a < 4 * PRODUCT_LEAF_SIZE as u64 {
+        return product(&(a..=b).collect::<Vec<_>>());
+    }
+
+    let m = a + (b - a) / 2;
+    range_product(a, m) * range_product(m + 1, b)
+}
@@ -0,0 +1,160 @@
+use num_bigint::BigUint;
+use num_traits::{Signed, Zero};
+
+use super::Int;
+
+impl Int {
+    /// Sets `self` to the `n`-th Fibonacci number `F(n)` and returns `self`,
+    /// where `F(0) = 0`, `F(1) = 1` and `F(k) = F(k-1) + F(k-2)`.
+    ///
+    /// The Fibonacci and Lucas numbers are the Lucas sequences `U(1, -1)` and
+    /// `V(1, -1)`. The pair `(L(n), L(n+1))` is computed by fast doubling, using
+    /// ```ignore
+    /// L(2k) = L(k)² - 2(-1)^k
+    /// L(2k+1) = L(k) L(k+1) - (-1)^k
+    /// ```
+    /// and `F(n)` follows from `5 F(n) = 2 L(n+1) - L(n)`.
+    pub fn fibonacci(&mut self, n: u64) -> &mut Self {
+        let (l, l1) = lucas_v(&n.into(), &BigUint::from(1u8), -1, None);
+        self.0 = fibonacci_from_lucas(l, l1, None).into();
+        self
+    }
+
+    /// Sets `self` to `F(n) mod m` and returns `self`, where `F(n)` is the `n`-th
+    /// Fibonacci number. Negative `n` follow `F(-n) = (-1)^(n+1) F(n)`. The
+    /// result is in `[0, m)`.
+    ///
+    /// # Panics
+    /// If `m` isn't positive.
+    pub fn fibonacci_mod(&mut self, n: &Self, m: &Self) -> &mut Self {
+        assert!(m.0.is_positive(), "modulus must be positive");
+        let m = m.0.magnitude();
+
+        // 5 F(k) ≡ 2 L(k+1) - L(k) (mod 5m) gives F(k) mod m even if 5 | m
+        let m5 = m * 5u8;
+        let k = n.0.magnitude();
+        let (l, l1) = lucas_v(k, &BigUint::from(1u8), -1, Some(&m5));
+        let f = fibonacci_from_lucas(l, l1, Some(&m5));
+        self.0 = if n.0.is_negative() && !k.bit(0) {
+            neg_mod(f, m)
+        } else {
+            f
+        }
+        .into();
+
+        self
+    }
+
+    /// Sets `self` to `F(n)` and `l` to `L(n)`, the `n`-th Fibonacci and Lucas
+    /// numbers, which are computed together anyway, and returns the pair.
+    /// See [Int::fibonacci].
+    pub fn fib_pair<'a, 'b>(&'a mut self, l: &'b mut Self, n: u64) -> (&'a mut Self, &'b mut Self) {
+        let (lk, lk1) = lucas_v(&n.into(), &BigUint::from(1u8), -1, None);
+        self.0 = fibonacci_from_lucas(lk.clone(), lk1, None).into();
+        l.0 = lk.into();
+        (self, l)
+    }
+
+    /// Sets `self` to the `n`-th Lucas number `L(n)` and returns `self`, where
+    /// `L(0) = 2`, `L(1) = 1` and `L(k) = L(k-1) + L(k-2)`. The Lucas numbers are
+    /// the Lucas sequence `V(1, -1)`, see [Int::fibonacci].
+    pub fn lucas(&mut self, n: u64) -> &mut Self {
+        self.0 = lucas_v(&n.into(), &BigUint::from(1u8), -1, None).0.into();
+        self
+    }
+
+    /// Sets `self` to `L(n) mod m` and returns `self`, where `L(n)` is the `n`-th
+    /// Lucas number. Negative `n` follow `L(-n) = (-1)^n L(n)`. The result is in
+    /// `[0, m)`.
+    ///
+    /// # Panics
+    /// If `m` isn't positive.
+    pub fn lucas_mod(&mut self, n: &Self, m: &Self) -> &mut Self {
+        assert!(m.0.is_positive(), "modulus must be positive");
+        let m = m.0.magnitude();
+
+        let k = n.0.magnitude();
+        let l = lucas_v(k, &BigUint::from(1u8), -1, Some(m)).0;
+        self.0 = if n.0.is_negative() && k.bit(0) {
+            neg_mod(l, m)
+        } else {
+            l
+        }
+        .into();
+
+        self
+    }
+}
+
+/// Returns `F(k) = (2 L(k+1) - L(k)) / 5`, modulo `n / 5` for `L(k)` and `L(k+1)`
+/// modulo `n`, which must be a multiple of 5.
+fn fibonacci_from_lucas(l: BigUint, l1: BigUint, n: Option<&BigUint>) -> BigUint {
+    add_or_sub(l1 << 1u8, &l, true, n) / 5u8
+}
+
+/// Returns `-x mod m` for `x` in [0, m).
+fn neg_mod(x: BigUint, m: &BigUint) -> BigUint {
+    if x.is_zero() {
+        x
+    } else {
+        m - x
+    }
+}
+
+/// Computes the pair `(V(k), V(k+1))` of the Lucas sequence `V(P, Q)` for
+/// `Q = ±1`, modulo `n` if given. Without a modulus, the sequence mustn't turn
+/// negative, which it doesn't for `P > 0` and `Q = -1`.
+///
+/// In general V(k) = α^k + β^k, where α and β are roots of x² - Px + Q.
+/// Crandall and Pomerance (p.147) observe that for 0 ≤ j ≤ k,
+/// ```ignore
+/// V(j+k) = V(j)V(k) - Q^j V(k-j).
+/// ```
+/// So in particular, to quickly double the subscript:
+/// ```ignore
+/// V(2k) = V(k)² - 2Q^k
+/// V(2k+1) = V(k) V(k+1) - P Q^k
+/// ```
+/// We can therefore start with k=0 and build up to k in log₂(k) steps.
+pub(super) fn lucas_v(k: &BigUint, p: &BigUint, q: i8, n: Option<&BigUint>) -> (BigUint, BigUint) {
+    assert!(q == 1 || q == -1, "Q must be ±1");
+    let reduce = |x: BigUint| match n {
+        Some(n) => x % n,
+        None => x,
+    };
+    let (two, p) = (reduce(BigUint::from(2u8)), reduce(p.clone()));
+
+    let mut vk = two.clone();
+    let mut vk1 = p.clone();
+    // whether Q^k = -1
+    let mut neg = false;
+    for i in (0..k.bits()).rev() {
+        // V(2k+1) = V(k) V(k+1) - P Q^k
+        let t = add_or_sub(&vk * &vk1, &p, !neg, n);
+        if k.bit(i) {
+            // k' = 2k+1
+            vk = t;
+            // V(k'+1) = V(2k+2) = V(k+1)² - 2Q^(k+1)
+            vk1 = add_or_sub(&vk1 * &vk1, &two, q > 0 || neg, n);
+            neg = q < 0;
+        } else {
+            // k' = 2k
+            vk1 = t;
+            // V(k') = V(2k) = V(k)² - 2Q^k
+            vk = add_or_sub(&vk * &vk, &two, !neg, n);
+            neg = false;
+        }
+    }
+
+    (vk, vk1)
+}
+
+/// Returns `x - c` if `sub`, else `x + c`, modulo `n` if given, for `c < n`.
+fn add_or_sub(x: BigUint, c: &BigUint, sub: bool, n: Option<&BigUint>) -> BigUint {
+    match n {
+        Some(n) if sub => (x + n - c) % n,
+        Some(n) => (x + c) % n,
+        None if sub => x - c,
+        None => x + c,
+    }
+}
@@ -0,0 +1,106 @@
+//! Encoding of [Int] values compatible with Go's `encoding.TextMarshaler`,
+//! `json.Marshaler` and `gob.GobEncoder` implementations for `*big.Int`.
+
+use std::error::Error;
+use std::fmt::{self, Display};
+
+use num_bigint::{BigInt, BigUint, Sign};
+
+use super::Int;
+
+/// Gob codec version. Permits backward-compatible changes to the encoding.
+const INT_GOB_VERSION: u8 = 1;
+
+/// The error type returned when decoding an [Int] by [Int::unmarshal_text],
+/// [Int::unmarshal_json] or [Int::gob_decode] fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum UnmarshalError {
+    /// The text isn't a number as accepted by [Int::set_string] with base 0.
+    InvalidText(Vec<u8>),
+    /// The gob encoding has an unsupported version.
+    UnsupportedGobVersion(u8),
+}
+
+impl Int {
+    /// Implements the `gob.GobEncoder` interface of Go: the result is a version
+    /// byte carrying the sign in its lowest bit, followed by [Int::bytes].
+    pub fn gob_encode(&self) -> Vec<u8> {
+        let mut b = INT_GOB_VERSION << 1;
+        if self.0.sign() == Sign::Minus {
+            b |= 1;
+        }
+
+        let mut out = vec![b];
+        out.extend(self.bytes());
+        out
+    }
+
+    /// Implements the `gob.GobDecoder` interface of Go. An empty `buf` decodes as 0.
+    pub fn gob_decode(&mut self, buf: &[u8]) -> Result<&mut Self, UnmarshalError> {
+        let (b, abs) = match buf.split_first() {
+            Some(v) => v,
+            None => {
+                self.0 = BigInt::default();
+                return Ok(self);
+            }
+        };
+
+        if b >> 1 != INT_GOB_VERSION {
+            return Err(UnmarshalError::UnsupportedGobVersion(b >> 1));
+        }
+
+        let sign = if b & 1 != 0 { Sign::Minus } else { Sign::Plus };
+        self.0 = BigInt::from_biguint(sign, BigUint::from_bytes_be(abs));
+        Ok(self)
+    }
+
+    /// Implements the `json.Marshaler` interface of Go: `self` is encoded as a bare
+    /// decimal JSON number.
+    pub fn marshal_json(&self) -> Vec<u8> {
+        self.marshal_text()
+    }
+
+    /// Implements the `encoding.TextMarshaler` interface of Go.
+    pub fn marshal_text(&self) -> Vec<u8> {
+        self.text(10).into_bytes()
+    }
+
+    /// Implements the `json.Unmarshaler` interface of Go. The JSON `null` leaves
+    /// `self` unchanged; anything else is decoded by [Int::unmarshal_text].
+    pub fn unmarshal_json(&mut self, text: &[u8]) -> Result<&mut Self, UnmarshalError> {
+        // Ignore null, like in the main JSON package.
+        if text == b"null" {
+            return Ok(self);
+        }
+
+        self.unmarshal_text(text)
+    }
+
+    /// Implements the `encoding.TextUnmarshaler` interface of Go. `text` must be a
+    /// number as accepted by [Int::set_string] with base 0. If `unmarshal_text`
+    /// fails, the value of `self` is undefined.
+    pub fn unmarshal_text(&mut self, text: &[u8]) -> Result<&mut Self, UnmarshalError> {
+        let mut r = text;
+        match self.scan(&mut r, 0) {
+            Ok((_, n)) if n == text.len() => Ok(self),
+            _ => Err(UnmarshalError::InvalidText(text.to_vec())),
+        }
+    }
+}
+
+impl Display for UnmarshalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidText(text) => write!(
+                f,
+                "math/big: cannot unmarshal {:?} into a *big.Int",
+                String::from_utf8_lossy(text)
+            ),
+            Self::UnsupportedGobVersion(v) => {
+                write!(f, "Int.GobDecode: encoding version {v} not supported")
+            }
+        }
+    }
+}
+
+impl Error for UnmarshalError {}
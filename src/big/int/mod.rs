@@ -1,13 +1,38 @@
 use std::{
+    cmp::Ordering,
     fmt::{Binary, Display, LowerHex, Octal, UpperHex},
+    io::{self, BufRead, Read},
     ops::{Neg, Not},
 };
 
 use num_bigint::{BigInt, BigUint, Sign};
 use num_integer::Integer;
-use num_traits::{One, Pow, Signed, Zero};
-
-use crate::big::MAX_BASE;
+use num_traits::{FromPrimitive, One, Pow, Signed, ToPrimitive, Zero};
+
+use crate::big::{Accuracy, Word, MAX_BASE};
+
+mod conv;
+mod dlog;
+pub mod factor;
+mod factorial;
+mod lucas;
+mod marsh;
+mod natconv;
+mod ops;
+mod prime;
+#[cfg(feature = "rand_core")]
+mod rng;
+mod root;
+#[cfg(feature = "serde")]
+pub mod serde;
+
+use conv::ByteScanner;
+
+pub use conv::{ParseIntError, TryFromIntError};
+pub use dlog::{dlog, dlog_factored};
+pub use marsh::UnmarshalError;
+#[cfg(feature = "rand_core")]
+pub use rng::RngReader;
 
 lazy_static::lazy_static! {
   static ref INT_ONE: Int = Int(BigInt::from(1i8));
@@ -41,7 +66,13 @@ lazy_static::lazy_static! {
 /// Operations always take mutable reference arguments (`&mut Int`) rather
 /// than `Int` values, and each unique `Int` value requires
 /// its own unique `&Int` reference. To "copy" an `Int` value, just use `Int::clone`.
-#[derive(Debug, Default, Clone, PartialEq, Eq)]
+///
+/// The `std::ops` operators are implemented for `Int`, `&Int` and primitive operands as
+/// well. `/` and `%` truncate like [Int::quo] and [Int::rem].
+///
+/// `Int` implements [Ord] and [Hash] consistently with [Int::cmp_i32], so it can be used
+/// as the key of ordered and hashed collections.
+#[derive(Debug, Default, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct Int(BigInt);
 
 impl Binary for Int {
@@ -110,28 +141,26 @@ impl Int {
     }
 
     /// Sets `self` to the binomial coefficient of (n, k) and returns `self`.
+    ///
+    /// The coefficient is assembled from its prime factorization, so that no
+    /// big divisions are needed.
     pub fn binomial(&mut self, n: i64, k: i64) -> &mut Self {
         if k > n {
             self.0.set_zero();
             return self;
         }
 
+        // reduce the number of factors by reducing k
         let mut k = k;
         if k > n - k {
-            k = n - k;
+            k = n - k; // C(n, k) == C(n, n-k)
         }
 
-        let mut i = BigInt::default();
-        let n = BigInt::from(n);
-        let k = BigInt::from(k);
-        let mut z = INT_ONE.0.clone();
-        while i < k {
-            z *= &n - &i;
-            i += 1;
-            z /= &i;
+        if k <= 0 {
+            return self.set_int64(1);
         }
 
-        self.0 = z;
+        self.0 = factorial::binomial(n as u64, k as u64).into();
         self
     }
 
@@ -151,15 +180,22 @@ impl Int {
         self.0.abs().bits() as usize
     }
 
-    /// Provides raw (unchecked but fast) access to `self` by returning its
-    /// absolute value as a little-endian Word slice. The result and `self` share
-    /// the same underlying array.
-    /// Bits is intended to support implementation of missing low-level `Int`
+    /// Provides raw (unchecked but fast) access to `self` by iterating over its
+    /// absolute value as little-endian [Word]s, without leading zero words. The
+    /// iterator borrows the limbs of `self` without copying them; iterate in the
+    /// reversed direction for the big-endian order.
+    /// Unlike Go's `Bits`, there's no slice to hand out, mutable or not, since the
+    /// underlying `num_bigint::BigInt` doesn't expose its limbs. Store computed
+    /// words with [Int::set_bits] instead.
+    /// `bits` is intended to support implementation of missing low-level `Int`
     /// functionality outside this package; it should be avoided otherwise.
-    ///
-    /// Use the iterator in the reversed direction will produce a big-endian Word slice.
-    pub fn bits(&self) -> Box<dyn DoubleEndedIterator<Item = u32> + '_> {
-        Box::new(self.0.iter_u32_digits())
+    pub fn bits(&self) -> impl DoubleEndedIterator<Item = Word> + ExactSizeIterator + '_ {
+        #[cfg(target_pointer_width = "64")]
+        let digits = self.0.iter_u64_digits();
+        #[cfg(not(target_pointer_width = "64"))]
+        let digits = self.0.iter_u32_digits();
+
+        digits.map(|d| d as Word)
     }
 
     /// Returns the absolute value of `self` as a big-endian byte slice.
@@ -175,20 +211,28 @@ impl Int {
         out
     }
 
-    /// Compares x and y and returns:
-    /// ```ignore
-    ///	-1 if x <  y
-    ///	 0 if x == y
-    ///	+1 if x >  y
-    /// ```
-    pub fn cmp(&self, y: &Self) -> i32 {
-        if self.0 < y.0 {
-            -1
-        } else if self.0 == y.0 {
-            0
-        } else {
-            1
-        }
+    /// Returns the i32 representation of `self`, or `None` if `self` cannot be
+    /// represented in an i32.
+    pub fn checked_int32(&self) -> Option<i32> {
+        self.0.to_i32()
+    }
+
+    /// Returns the i64 representation of `self`, or `None` if `self` cannot be
+    /// represented in an i64.
+    pub fn checked_int64(&self) -> Option<i64> {
+        self.0.to_i64()
+    }
+
+    /// Returns the u32 representation of `self`, or `None` if `self` cannot be
+    /// represented in a u32.
+    pub fn checked_uint32(&self) -> Option<u32> {
+        self.0.to_u32()
+    }
+
+    /// Returns the u64 representation of `self`, or `None` if `self` cannot be
+    /// represented in a u64.
+    pub fn checked_uint64(&self) -> Option<u64> {
+        self.0.to_u64()
     }
 
     /// Compares the absolute values of x and y and returns:
@@ -197,16 +241,26 @@ impl Int {
     ///	 0 if |x| == |y|
     ///	+1 if |x| >  |y|
     /// ```
+    /// See [Int::cmp_magnitude] for the [Ordering]-returning variant.
     pub fn cmp_abs(&self, y: &Self) -> i32 {
-        let a = self.0.abs();
-        let b = y.0.abs();
-        if a < b {
-            -1
-        } else if a == b {
-            0
-        } else {
-            1
-        }
+        self.cmp_magnitude(y) as i32
+    }
+
+    /// Compares x and y and returns:
+    /// ```ignore
+    ///	-1 if x <  y
+    ///	 0 if x == y
+    ///	+1 if x >  y
+    /// ```
+    /// This is Go's `Int.Cmp`; the [Ord] implementation of `Int` returns an
+    /// [Ordering] instead.
+    pub fn cmp_i32(&self, y: &Self) -> i32 {
+        self.cmp(y) as i32
+    }
+
+    /// Compares the absolute values of x and y, i.e. `|x|.cmp(&|y|)`.
+    pub fn cmp_magnitude(&self, y: &Self) -> Ordering {
+        self.0.magnitude().cmp(y.0.magnitude())
     }
 
     /// Sets `self` to the quotient x/y for y != 0 and returns self.
@@ -331,6 +385,48 @@ impl Int {
         out
     }
 
+    /// Returns the float32 value nearest `self`, rounding half to even, and an
+    /// indication of any rounding that occurred. If `self` is too large to be
+    /// represented by a float32, the result is ±Inf with accuracy [Accuracy::Above]
+    /// or [Accuracy::Below] respectively.
+    pub fn float32(&self) -> (f32, Accuracy) {
+        let (m, e, acc) = round_to_precision(self.0.magnitude(), f32::MANTISSA_DIGITS);
+
+        // m < 2²⁴, so only the exponent can overflow
+        let (f, acc) = if e + f32::MANTISSA_DIGITS as u64 > f32::MAX_EXP as u64 {
+            (f32::INFINITY, Accuracy::Above)
+        } else {
+            ((m as f32) * 2f32.powi(e as i32), acc)
+        };
+
+        if self.0.is_negative() {
+            (-f, -acc)
+        } else {
+            (f, acc)
+        }
+    }
+
+    /// Returns the float64 value nearest `self`, rounding half to even, and an
+    /// indication of any rounding that occurred. If `self` is too large to be
+    /// represented by a float64, the result is ±Inf with accuracy [Accuracy::Above]
+    /// or [Accuracy::Below] respectively.
+    pub fn float64(&self) -> (f64, Accuracy) {
+        let (m, e, acc) = round_to_precision(self.0.magnitude(), f64::MANTISSA_DIGITS);
+
+        // m < 2⁵³, so only the exponent can overflow
+        let (f, acc) = if e + f64::MANTISSA_DIGITS as u64 > f64::MAX_EXP as u64 {
+            (f64::INFINITY, Accuracy::Above)
+        } else {
+            ((m as f64) * 2f64.powi(e as i32), acc)
+        };
+
+        if self.0.is_negative() {
+            (-f, -acc)
+        } else {
+            (f, acc)
+        }
+    }
+
     /// Sets `self` to the greatest common divisor of `a` and `b` and returns `z`.
     /// If `x` or `y` are not `None`, GCD sets their value such that `z = a*x + b*y`.
     ///
@@ -375,24 +471,103 @@ impl Int {
         self
     }
 
+    /// Returns the floor of the logarithm of `self` to the given base, i.e. the
+    /// largest `k` such that `baseᵏ ≤ self`.
+    ///
+    /// # Panics
+    /// If `self` <= 0 or `base` < 2.
+    pub fn ilog(&self, base: &Self) -> usize {
+        assert!(
+            self.0.is_positive(),
+            "argument of integer logarithm must be positive"
+        );
+        assert!(
+            base.0 > BigInt::one(),
+            "base of integer logarithm must be at least 2"
+        );
+
+        let (x, b) = (self.0.magnitude(), base.0.magnitude());
+        if b.count_ones() == 1 {
+            return (x.bits() as usize - 1) / (b.bits() as usize - 1);
+        }
+
+        // The float estimate is off by at most one for any realistic size, so start
+        // one below it and correct exactly.
+        let k = (log2(x) / log2(b)) as usize;
+        let mut k = k.saturating_sub(1);
+        let mut p = Pow::pow(b, k);
+        while p > *x {
+            p /= b;
+            k -= 1;
+        }
+        loop {
+            p *= b;
+            if p > *x {
+                return k;
+            }
+            k += 1;
+        }
+    }
+
+    /// Returns the floor of the decimal logarithm of `self`, i.e. the number of
+    /// decimal digits of `self` minus one.
+    ///
+    /// # Panics
+    /// If `self` <= 0.
+    pub fn ilog10(&self) -> usize {
+        self.ilog(&Int::new(10))
+    }
+
+    /// Returns the floor of the binary logarithm of `self`, i.e. `self.bit_len() - 1`.
+    ///
+    /// # Panics
+    /// If `self` <= 0.
+    pub fn ilog2(&self) -> usize {
+        assert!(
+            self.0.is_positive(),
+            "argument of integer logarithm must be positive"
+        );
+
+        self.bit_len() - 1
+    }
+
+    /// Returns the i32 representation of `self`.
+    /// If `self` cannot be represented in an i32, the result is the low 32 bits of
+    /// its two's complement representation, i.e. `self.int64() as i32`.
+    pub fn int32(&self) -> i32 {
+        self.int64() as i32
+    }
+
     /// Returns the i64 representation of `self`.
-    /// If `self` cannot be represented in an i64, the result is undefined.
+    /// If `self` cannot be represented in an i64, the result is the low 64 bits of
+    /// its two's complement representation, just like Go.
     pub fn int64(&self) -> i64 {
-        if let Ok(v) = self.0.clone().try_into() {
-            v
+        let v = low64(&self.0) as i64;
+        if self.0.is_negative() {
+            v.wrapping_neg()
         } else {
-            0
+            v
         }
     }
 
+    /// Reports whether `self` can be represented as an int32.
+    pub fn is_int32(&self) -> bool {
+        self.checked_int32().is_some()
+    }
+
     /// Reports whether `self` can be represented as an int64.
     pub fn is_int64(&self) -> bool {
-        <BigInt as TryInto<i64>>::try_into(self.0.clone()).is_ok()
+        self.checked_int64().is_some()
+    }
+
+    /// Reports whether `self` can be represented as a uint32.
+    pub fn is_uint32(&self) -> bool {
+        self.checked_uint32().is_some()
     }
 
     /// Reports whether `self` can be represented as a uint64.
     pub fn is_uint64(&self) -> bool {
-        <BigInt as TryInto<u64>>::try_into(self.0.clone()).is_ok()
+        self.checked_uint64().is_some()
     }
 
     /// Sets `self = x << n` and returns `self`.
@@ -510,12 +685,7 @@ impl Int {
             (a as u64, b as u64, 1)
         };
 
-        let mut p = BigInt::one();
-        for i in a..=b {
-            p *= i;
-        }
-
-        self.0 = p * sign;
+        self.0 = BigInt::from(factorial::range_product(a, b)) * sign;
 
         self
     }
@@ -538,85 +708,6 @@ impl Int {
         self
     }
 
-    /*
-    pub fn probably_prime(&mut self, n: usize) -> bool {
-        //assert!(n >= 0, "negative n for probably_prime");
-
-        let v = &self.0;
-
-        if v.is_negative() || v.is_zero() {
-            return false;
-        }
-
-        const PRIME_BIT_MASK: u64 = (1 << 2)
-            | (1 << 3)
-            | (1 << 5)
-            | (1 << 7)
-            | (1 << 11)
-            | (1 << 13)
-            | (1 << 17)
-            | (1 << 19)
-            | (1 << 23)
-            | (1 << 29)
-            | (1 << 31)
-            | (1 << 37)
-            | (1 << 41)
-            | (1 << 43)
-            | (1 << 47)
-            | (1 << 53)
-            | (1 << 59)
-            | (1 << 61);
-
-        let w = v.iter_u64_digits().next().expect("as u64");
-        if (v.bits() < 64) && (w < 64) {
-            return (PRIME_BIT_MASK & (1 << w)) != 0;
-        }
-
-        if (w & 1) == 0 {
-            return false;
-        }
-
-        const PRIMES_A: u64 = 3 * 5 * 7 * 11 * 13 * 17 * 19 * 23 * 37;
-        const PRIMES_B: u64 = 29 * 31 * 41 * 43 * 47 * 53;
-
-        let (r_a, r_b): (u32, u32) = match W {
-            32 => (
-                (v % PRIMES_A).to_u32().unwrap(),
-                (v % PRIMES_B).to_u32().unwrap(),
-            ),
-            64 => {
-                let r = v % ((PRIMES_A * PRIMES_B) & (M as u64));
-                (
-                    (&r % PRIMES_A).to_u32().unwrap(),
-                    (&r % PRIMES_B).to_u32().unwrap(),
-                )
-            }
-            _ => panic!("math::big: invalid word size"),
-        };
-
-        if (r_a % 3 == 0)
-            || (r_a % 5 == 0)
-            || (r_a % 7 == 0)
-            || (r_a % 11 == 0)
-            || (r_a % 13 == 0)
-            || (r_a % 17 == 0)
-            || (r_a % 19 == 0)
-            || (r_a % 23 == 0)
-            || (r_a % 37 == 0)
-            || (r_b % 29 == 0)
-            || (r_b % 31 == 0)
-            || (r_b % 41 == 0)
-            || (r_b % 43 == 0)
-            || (r_b % 47 == 0)
-            || (r_b % 53 == 0)
-        {
-            return false;
-        }
-
-        probably_prime_miller_rabin(v, n + 1, true) && probably_prime_lucas(v)
-    }
-    */
-
     /// Sets `self` to the quotient `x/y` for `y` != 0 and returns `self`.
     /// If `y` == 0, a division-by-zero run-time panic occurs.
     /// `quo` implements truncated division (like Go); see [Int::quo_rem] for more details.
@@ -650,30 +741,81 @@ impl Int {
         (self, r)
     }
 
-    /// Sets `self` to a pseudo-random number in `[0, n)` and returns `self`.
+    /// Sets `self` to a uniformly distributed pseudo-random number in `[0, n)` and
+    /// returns `self`. If `n` <= 0, `self` is set to 0.
+    ///
+    /// Random bytes are read from `rnd`, which may be any [io::Read]. With the
+    /// `rand_core` feature, a `rand_core::RngCore` is wrapped in a `RngReader`.
+    /// Candidates of the bit length of `n-1` are drawn until one is below `n`, so
+    /// fewer than two candidates are needed on average.
     ///
     /// Security depends on the randomness provided by `rnd`.
+    ///
+    /// # Panics
+    /// If reading from `rnd` fails.
     pub fn rand<R>(&mut self, rnd: &mut R, n: &Self) -> &mut Self
     where
-        R: std::io::Read,
+        R: Read + ?Sized,
     {
         if !n.0.is_positive() {
             self.0.set_zero();
             return self;
         }
 
-        let mut buf = vec![0u8; n.bit_len() / 8];
+        let max = n.0.magnitude() - 1u8;
+        let bits = max.bits() as usize;
+        if bits == 0 {
+            // n == 1
+            self.0.set_zero();
+            return self;
+        }
+
+        let mut buf = vec![0u8; (bits - 1) / 8 + 1];
         self.0 = loop {
-            rnd.read_exact(buf.as_mut_slice()).expect("fill buf");
-            let v = BigInt::from_bytes_be(Sign::Plus, buf.as_slice());
-            if v < n.0 {
-                break v;
+            let v = rand_bytes(rnd, &mut buf, bits);
+            if v <= max {
+                break v.into();
             }
         };
 
         self
     }
 
+    /// Sets `self` to a uniformly distributed pseudo-random number in `[0, 2^bits)`
+    /// and returns `self`. See [Int::rand] for the accepted sources of randomness.
+    ///
+    /// # Panics
+    /// If reading from `rnd` fails.
+    pub fn rand_bits<R>(&mut self, rnd: &mut R, bits: usize) -> &mut Self
+    where
+        R: Read + ?Sized,
+    {
+        if bits == 0 {
+            self.0.set_zero();
+            return self;
+        }
+
+        let mut buf = vec![0u8; (bits - 1) / 8 + 1];
+        self.0 = rand_bytes(rnd, &mut buf, bits).into();
+        self
+    }
+
+    /// Sets `self` to a uniformly distributed pseudo-random number in `[lo, hi)` and
+    /// returns `self`. See [Int::rand] for the accepted sources of randomness.
+    ///
+    /// # Panics
+    /// If `lo >= hi` or reading from `rnd` fails.
+    pub fn rand_range<R>(&mut self, rnd: &mut R, lo: &Self, hi: &Self) -> &mut Self
+    where
+        R: Read + ?Sized,
+    {
+        assert!(lo < hi, "empty range");
+
+        self.rand(rnd, &Int(&hi.0 - &lo.0));
+        self.0 += &lo.0;
+        self
+    }
+
     /// Sets `self` to the remainder `x%y` for `y` != 0 and returns `self`.
     /// If `self` == 0, a division-by-zero run-time panic occurs.
     /// `rem` implements truncated modulus (like Go); see [Int::quo_rem] for more details.
@@ -692,6 +834,106 @@ impl Int {
         self
     }
 
+    /// Reads an optionally signed number in the given base from `r`, sets `self` to
+    /// its value and returns the actual base together with the number of bytes read.
+    ///
+    /// The prefix, digit and underscore rules are those of [Int::set_string], except
+    /// that scanning stops right before the first byte that can't continue the number
+    /// instead of failing; that byte is left in `r`. For base 0, a lone "0" (possibly
+    /// followed by other bytes) reads as 0 in base 10.
+    ///
+    /// A [ParseIntError] is reported as an [io::Error] of kind
+    /// [InvalidData][io::ErrorKind::InvalidData] (or [InvalidInput][io::ErrorKind::InvalidInput]
+    /// for a bad `base`) wrapping it, with indices counting from the first byte read.
+    /// The value of `self` is undefined if an error is returned.
+    ///
+    /// # Example
+    /// ```
+    #[doc = include_str!("../../../examples/big_int_scan.rs")]
+    /// ```
+    pub fn scan<R: BufRead>(&mut self, r: &mut R, base: u8) -> io::Result<(u8, usize)> {
+        if base != 0 && !(2..=MAX_BASE).contains(&base) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                ParseIntError::InvalidBase,
+            ));
+        }
+
+        let mut r = ByteScanner::new(r);
+
+        let sign = match r.peek()? {
+            Some(b'-') => Some(Sign::Minus),
+            Some(b'+') => Some(Sign::Plus),
+            _ => None,
+        };
+        if sign.is_some() {
+            r.consume();
+        }
+
+        // `prev` mirrors Go: '0' after a digit or base prefix, '_' after an underscore.
+        let (mut b, mut prefix, mut prev) = (base, 0u8, b'.');
+        if base == 0 {
+            b = 10;
+            if r.peek()? == Some(b'0') {
+                r.consume();
+                prev = b'0';
+                (b, prefix) = match r.peek()? {
+                    Some(b'b' | b'B') => (2, b'b'),
+                    Some(b'o' | b'O') => (8, b'o'),
+                    Some(b'x' | b'X') => (16, b'x'),
+                    _ => (8, b'0'),
+                };
+                if prefix != b'0' {
+                    r.consume();
+                }
+            }
+        }
+
+        let lookup = new_lookup_table(b);
+        let mut digits = vec![];
+        let mut bad_underscore = None;
+        while let Some(c) = r.peek()? {
+            if c == b'_' && base == 0 {
+                if prev != b'0' && bad_underscore.is_none() {
+                    bad_underscore = Some(r.consumed());
+                }
+                prev = b'_';
+            } else {
+                match lookup[c as usize] {
+                    0xff => break,
+                    d => digits.push(d),
+                }
+                prev = b'0';
+            }
+            r.consume();
+        }
+
+        let n = r.consumed();
+        let err = if let Some(index) = bad_underscore {
+            Some(ParseIntError::BadUnderscore { index })
+        } else if prev == b'_' {
+            Some(ParseIntError::BadUnderscore { index: n - 1 })
+        } else if digits.is_empty() && prefix != b'0' {
+            Some(ParseIntError::Empty)
+        } else {
+            None
+        };
+        if let Some(err) = err {
+            return Err(io::Error::new(io::ErrorKind::InvalidData, err));
+        }
+
+        if digits.is_empty() {
+            // a lone "0" prefix
+            self.0 = BigInt::zero();
+            return Ok((10, n));
+        }
+
+        let v = natconv::from_digits(&digits, b);
+        self.0 = BigInt::from_biguint(sign.unwrap_or(Sign::Plus), v);
+
+        Ok((b, n))
+    }
+
     /// Sets `self` to `x` and returns `self`.
     pub fn set(&mut self, x: &Self) -> &mut Self {
         self.0 = x.0.clone();
@@ -708,12 +950,12 @@ impl Int {
     }
 
     /// Provides raw (unchecked but fast) access to `self` by setting its
-    /// value to abs, interpreted as a little-endian Word slice, and returning
-    /// `self`.
+    /// value to abs, interpreted as a little-endian [Word] slice, and returning
+    /// `self`. The words are copied into `self`.
     /// `set_bits` is intended to support implementation of missing low-level `Int`
     /// functionality outside this package; it should be avoided otherwise.
-    pub fn set_bits(&mut self, abs: &[u32]) -> &mut Self {
-        self.0 = BigInt::new(Sign::Plus, abs.to_vec());
+    pub fn set_bits(&mut self, abs: &[Word]) -> &mut Self {
+        self.0 = BigInt::from_biguint(Sign::Plus, from_words(abs));
         self
     }
 
@@ -724,6 +966,13 @@ impl Int {
         self
     }
 
+    /// Sets `self` to the integer part of `x`, truncating toward zero, and returns
+    /// `self`. If `x` is NaN or ±Inf, `self` is unchanged and `None` is returned.
+    pub fn set_float64(&mut self, x: f64) -> Option<&mut Self> {
+        self.0 = BigInt::from_f64(x)?;
+        Some(self)
+    }
+
     /// Sets `self` to `x` and returns `self`.
     pub fn set_int64(&mut self, x: i64) -> &mut Self {
         self.0 = BigInt::from(x);
@@ -731,9 +980,9 @@ impl Int {
     }
 
     /// Sets `self` to the value of `s`, interpreted in the given `base`,
-    /// and returns `self` and `Option<&mut Self>` indicating success. The entire string
-    /// (not just a prefix) must be valid for success. If `set_string` fails,
-    /// the value of `self` is undefined but the returned value is `None`.
+    /// and returns `self` on success. The entire string (not just a prefix)
+    /// must be valid for success. If `set_string` fails, the value of `self` is
+    /// undefined and the returned [ParseIntError] tells what went wrong.
     ///
     /// The base argument must be 0 or a value between 2 and [MAX_BASE][crate::big::MAX_BASE].
     /// For base 0, the number prefix determines the actual base: A prefix of
@@ -757,12 +1006,12 @@ impl Int {
     /// ```
     #[doc = include_str!("../../../examples/big_int_set_string.rs")]
     /// ```
-    pub fn set_string(&mut self, s: &str, base: u8) -> Option<&mut Self> {
-        if s.is_empty() || !((base == 0) || ((2 <= base) && (base <= MAX_BASE))) {
-            return None;
+    pub fn set_string(&mut self, s: &str, base: u8) -> Result<&mut Self, ParseIntError> {
+        if !((base == 0) || ((2 <= base) && (base <= MAX_BASE))) {
+            return Err(ParseIntError::InvalidBase);
         }
 
-        let (s, sign) = if let Some(ss) = s.strip_prefix('-') {
+        let (ss, sign) = if let Some(ss) = s.strip_prefix('-') {
             (ss, Sign::Minus)
         } else if let Some(ss) = s.strip_prefix('+') {
             (ss, Sign::Plus)
@@ -770,56 +1019,43 @@ impl Int {
             (s, Sign::Plus)
         };
 
-        if s == "0" {
+        if ss == "0" {
             // "0"/"-0"/"+0"
-            return Some(self.set_int64(0));
+            return Ok(self.set_int64(0));
         }
 
         if base != 0 {
-            self.0 = match scan_disallowing_underscores(s.as_bytes(), base) {
-                Ok(v) => BigInt::from_biguint(sign, v),
-                Err(_) => {
-                    //println!("scan '{s}' for base={base} failed: {err}");
-                    return None;
-                }
-            };
-
-            return Some(self);
+            let offset = s.len() - ss.len();
+            let v = scan_disallowing_underscores(ss.as_bytes(), base, offset)?;
+            self.0 = BigInt::from_biguint(sign, v);
+            return Ok(self);
         }
 
         let mut has_base_prefix = true;
-        let (s, base) = if let Some(s) = s.strip_prefix("0b") {
-            (s, 2)
-        } else if let Some(s) = s.strip_prefix("0B") {
-            (s, 2)
-        } else if let Some(s) = s.strip_prefix("0o") {
-            (s, 8)
-        } else if let Some(s) = s.strip_prefix("0O") {
-            (s, 8)
-        } else if let Some(s) = s.strip_prefix("0x") {
-            (s, 16)
-        } else if let Some(s) = s.strip_prefix("0X") {
-            (s, 16)
-        } else if let Some(s) = s.strip_prefix('0') {
-            (s, 8)
+        let (ss, base) = if let Some(ss) = ss.strip_prefix("0b") {
+            (ss, 2)
+        } else if let Some(ss) = ss.strip_prefix("0B") {
+            (ss, 2)
+        } else if let Some(ss) = ss.strip_prefix("0o") {
+            (ss, 8)
+        } else if let Some(ss) = ss.strip_prefix("0O") {
+            (ss, 8)
+        } else if let Some(ss) = ss.strip_prefix("0x") {
+            (ss, 16)
+        } else if let Some(ss) = ss.strip_prefix("0X") {
+            (ss, 16)
+        } else if let Some(ss) = ss.strip_prefix('0') {
+            (ss, 8)
         } else {
             has_base_prefix = false;
-            (s, 10)
+            (ss, 10)
         };
 
-        if s.is_empty() {
-            return None;
-        }
-
-        self.0 = match scan_allowing_underscores(s.as_bytes(), base, has_base_prefix) {
-            Ok(v) => BigInt::from_biguint(sign, v),
-            Err(_) => {
-                //println!("scan '{s}' failed: {err}");
-                return None;
-            }
-        };
+        let offset = s.len() - ss.len();
+        let v = scan_allowing_underscores(ss.as_bytes(), base, has_base_prefix, offset)?;
+        self.0 = BigInt::from_biguint(sign, v);
 
-        Some(self)
+        Ok(self)
     }
 
     /// Sets `self` to `x` and returns `self`.
@@ -880,28 +1116,11 @@ impl Int {
             MAX_BASE
         );
 
-        if self.0.is_zero() {
-            return "0".to_string();
-        }
-
-        let mut v = self.0.clone();
-        let mut buf = vec![];
-
-        while !v.is_zero() {
-            let w: usize = {
-                let (_, b) = (&v % base).to_bytes_le();
-                b[0] as usize
-            };
-            buf.push(INVERSE_LOOKUP_TABLE[w]);
-            v = v / base;
-        }
-
+        let mut buf = natconv::itoa(self.0.magnitude(), base);
         if self.0.is_negative() {
-            buf.push(b'-');
+            buf.insert(0, b'-');
         }
 
-        buf.reverse();
-
         unsafe { String::from_utf8_unchecked(buf) }
     }
 
@@ -911,10 +1130,43 @@ impl Int {
         self.0.trailing_zeros().unwrap_or_default() as usize
     }
 
-    /// returns the uint64 representation of `self`.
-    /// If `self` cannot be represented in a uint64, the result is undefined.
+    /// Returns the uint32 representation of `self`.
+    /// If `self` cannot be represented in a uint32, the result is the low 32 bits
+    /// of `|self|`, i.e. `self.uint64() as u32`.
+    pub fn uint32(&self) -> u32 {
+        self.uint64() as u32
+    }
+
+    /// Returns the uint64 representation of `self`.
+    /// If `self` cannot be represented in a uint64, the result is the low 64 bits
+    /// of `|self|`, just like Go: the sign is ignored. Use [Int::wrapping_uint64]
+    /// for the low 64 bits of the two's complement representation.
     pub fn uint64(&self) -> u64 {
-        self.0.iter_u64_digits().next().unwrap_or_default()
+        low64(&self.0)
+    }
+
+    /// Returns the low 32 bits of the two's complement representation of `self`,
+    /// as `self.wrapping_int64() as i32` would.
+    pub fn wrapping_int32(&self) -> i32 {
+        self.int64() as i32
+    }
+
+    /// Returns the low 64 bits of the two's complement representation of `self`.
+    /// This is the same as [Int::int64].
+    pub fn wrapping_int64(&self) -> i64 {
+        self.int64()
+    }
+
+    /// Returns the low 32 bits of the two's complement representation of `self`,
+    /// so that `-1` maps to `u32::MAX`.
+    pub fn wrapping_uint32(&self) -> u32 {
+        self.int64() as u32
+    }
+
+    /// Returns the low 64 bits of the two's complement representation of `self`,
+    /// so that `-1` maps to `u64::MAX`.
+    pub fn wrapping_uint64(&self) -> u64 {
+        self.int64() as u64
     }
 
     /// Sets `self = x ^ y` and returns `self`.
@@ -1011,6 +1263,77 @@ impl Int {
     }
 }
 
+/// Solves the system of congruences `x ≡ residues[i] (mod moduli[i])` by the
+/// Chinese remainder theorem. It returns `Some((x, m))`, where `m` is the lcm of
+/// the moduli and `0 <= x < m`, so that the solutions are exactly the integers
+/// congruent to `x` modulo `m`. The moduli needn't be pairwise coprime, in which
+/// case the congruences may contradict each other and `None` is returned. An
+/// empty system is solved by `Some((0, 1))`.
+///
+/// The congruences are combined pairwise in a balanced tree, so that the big
+/// multiplications and divisions are done on operands of similar sizes.
+///
+/// # Panics
+/// If `residues` and `moduli` differ in length, or a modulus isn't positive.
+pub fn crt(residues: &[Int], moduli: &[Int]) -> Option<(Int, Int)> {
+    assert_eq!(
+        residues.len(),
+        moduli.len(),
+        "residues and moduli must be of the same length"
+    );
+    assert!(
+        moduli.iter().all(|m| m.0.is_positive()),
+        "moduli must be positive"
+    );
+
+    crt_tree(residues, moduli)
+}
+
+fn crt_tree(residues: &[Int], moduli: &[Int]) -> Option<(Int, Int)> {
+    match moduli.len() {
+        0 => return Some((Int::default(), Int::new(1))),
+        1 => {
+            return Some((
+                Int(residues[0].0.mod_floor(&moduli[0].0)),
+                moduli[0].clone(),
+            ))
+        }
+        _ => {}
+    }
+
+    let mid = moduli.len() / 2;
+    let lo = crt_tree(&residues[..mid], &moduli[..mid])?;
+    let hi = crt_tree(&residues[mid..], &moduli[mid..])?;
+    crt_pair(lo, hi)
+}
+
+/// Combines `x ≡ r1 (mod m1)` and `x ≡ r2 (mod m2)` for `0 <= ri < mi`.
+fn crt_pair((r1, m1): (Int, Int), (r2, m2): (Int, Int)) -> Option<(Int, Int)> {
+    // u·m1 ≡ g (mod m2), and x = r1 + m1·t must satisfy m1·t ≡ r2 - r1 (mod m2),
+    // which is solvable iff g divides r2 - r1
+    let (mut g, mut u) = (Int::default(), Int::default());
+    g.gcd(Some(&mut u), None, &m1, &m2);
+
+    let (q, rem) = (r2.0 - &r1.0).div_rem(&g.0);
+    if !rem.is_zero() {
+        return None;
+    }
+
+    let m2 = m2.0 / &g.0;
+    let t = (q * u.0).mod_floor(&m2);
+    Some((Int(r1.0 + &m1.0 * t), Int(m1.0 * m2)))
+}
+
+/// Returns the value of the little-endian words `abs`.
+fn from_words(abs: &[Word]) -> BigUint {
+    // BigUint::new drops the leading zero digits
+    BigUint::new(
+        abs.iter()
+            .flat_map(|&w| (0..Word::BITS / 32).map(move |i| (w as u64 >> (32 * i)) as u32))
+            .collect(),
+    )
+}
+
 /// Returns the Jacobi symbol (x/y), either +1, -1, or 0.
 /// The y argument must be an odd integer.
 pub fn jacobi(x: &Int, y: &Int) -> i32 {
@@ -1068,18 +1391,77 @@ pub fn jacobi(x: &Int, y: &Int) -> i32 {
     }
 }
 
-// fn probably_prime_lucas(v: &BigInt) -> bool {}
+/// Returns the Kronecker symbol (x/y), either +1, -1, or 0.
+///
+/// The Kronecker symbol extends the Jacobi symbol to all integers y, by
+/// ```ignore
+/// (x/-1) = -1 if x < 0, +1 otherwise
+/// (x/2)  = 0 if x is even, +1 if x ≡ ±1 (mod 8), -1 if x ≡ ±3 (mod 8)
+/// (x/0)  = 1 if x = ±1, 0 otherwise
+/// ```
+/// and multiplicativity in y. For odd positive y it equals [jacobi]`(x, y)`.
+pub fn kronecker(x: &Int, y: &Int) -> i32 {
+    if y.0.is_zero() {
+        return if x.0.magnitude().is_one() { 1 } else { 0 };
+    }
 
-//fn probably_prime_miller_rabin(v: &BigInt, reps: usize, force2: bool) -> bool {
-//    let nm1 = v - 1;
-//    let k = v.trailing_zeros().unwrap_or_default();
-//    let q = nm1 << k;
-//
-//    let nm3 = nm1 - 2;
-//}
+    let mut j = 1;
+    if y.0.is_negative() && x.0.is_negative() {
+        j = -1;
+    }
 
-fn new_lookup_table(base: u8) -> [u8; 128] {
-    let mut out = [0xffu8; 128];
+    let s = y.trailing_zero_bits();
+    if s > 0 {
+        if x.0.is_even() {
+            return 0;
+        }
+        // (x/2) only depends on x mod 8
+        let x8 = x.0.mod_floor(&BigInt::from(8)).to_u8().expect("fit in u8");
+        if (s & 1) != 0 && (x8 == 3 || x8 == 5) {
+            j = -j;
+        }
+    }
+
+    let b = Int(BigInt::from(y.0.magnitude() >> s));
+    j * jacobi(x, &b)
+}
+
+/// Returns the Legendre symbol (a/p), either +1, -1, or 0, which tells whether
+/// a is a quadratic residue modulo p. The p argument must be an odd prime, which
+/// is checked in debug builds only. Otherwise the result equals [jacobi]`(a, p)`.
+pub fn legendre(a: &Int, p: &Int) -> i32 {
+    debug_assert!(
+        p.0.is_odd() && p.probably_prime(0),
+        "big: invalid 2nd argument to legendre: need odd prime but got {}",
+        p.0
+    );
+
+    jacobi(a, p)
+}
+
+/// Returns `log2(x)` for `x` > 0 in double precision.
+fn log2(x: &BigUint) -> f64 {
+    let bits = x.bits();
+    let shift = bits.saturating_sub(64);
+    let top = (x >> shift).to_u64().expect("fit in u64");
+
+    shift as f64 + (top as f64).log2()
+}
+
+/// Returns the least significant 64 bits of `|x|`.
+fn low64(x: &BigInt) -> u64 {
+    low64_u(x.magnitude())
+}
+
+fn low64_u(x: &BigUint) -> u64 {
+    x.iter_u64_digits().next().unwrap_or_default()
+}
+
+/// Returns a table mapping every byte to its digit value in `base`, or 0xff if the
+/// byte isn't a valid digit. The table covers all 256 byte values, so that
+/// non-ASCII input simply fails to match.
+fn new_lookup_table(base: u8) -> [u8; 256] {
+    let mut out = [0xffu8; 256];
 
     for c in b'0'..=b'9'.min(b'0' + base - 1) {
         out[c as usize] = c - b'0';
@@ -1100,7 +1482,7 @@ fn new_lookup_table(base: u8) -> [u8; 128] {
         }
     } else {
         let x = base - 36;
-        for c in b'a'..(b'a' + x) {
+        for c in b'a'..=b'z' {
             out[c as usize] = c - b'a' + 10;
         }
 
@@ -1112,45 +1494,123 @@ fn new_lookup_table(base: u8) -> [u8; 128] {
     out
 }
 
-fn scan_allowing_underscores(s: &[u8], base: u8, has_base_prefix: bool) -> Result<BigUint, String> {
+/// Fills `buf` from `rnd` and returns its big-endian value truncated to `bits` bits,
+/// where `buf` holds exactly enough bytes for `bits` > 0 bits.
+fn rand_bytes<R: Read + ?Sized>(rnd: &mut R, buf: &mut [u8], bits: usize) -> BigUint {
+    rnd.read_exact(buf).expect("fill buf");
+
+    // clear the bits above the requested length in the top byte
+    let b = bits % 8;
+    if b != 0 {
+        buf[0] &= (1u8 << b) - 1;
+    }
+
+    BigUint::from_bytes_be(buf)
+}
+
+/// Rounds `x` half to even to `prec` significant bits, returning `(m, e, acc)` such that
+/// `m * 2^e` is the rounded value with `m < 2^prec`. `acc` tells how the rounded value
+/// relates to `x`.
+fn round_to_precision(x: &BigUint, prec: u32) -> (u64, u64, Accuracy) {
+    let n = x.bits();
+    if n <= prec as u64 {
+        return (low64_u(x), 0, Accuracy::Exact);
+    }
+
+    // keep one extra bit to decide the rounding direction
+    let shift = n - prec as u64 - 1;
+    let m = low64_u(&(x >> shift));
+    let sticky = x.trailing_zeros().unwrap_or_default() < shift;
+
+    let (half, mut m, mut e) = ((m & 1) != 0, m >> 1, shift + 1);
+    let acc = if half && (sticky || (m & 1) != 0) {
+        m += 1;
+        if m == (1 << prec) {
+            m >>= 1;
+            e += 1;
+        }
+        Accuracy::Above
+    } else if half || sticky {
+        Accuracy::Below
+    } else {
+        Accuracy::Exact
+    };
+
+    (m, e, acc)
+}
+
+/// Scans the digits `s` in `base`, where `offset` is the index of `s` within the
+/// whole input, used for error reporting.
+fn scan_allowing_underscores(
+    s: &[u8],
+    base: u8,
+    has_base_prefix: bool,
+    offset: usize,
+) -> Result<BigUint, ParseIntError> {
+    if s.is_empty() {
+        return Err(ParseIntError::Empty);
+    }
+
     let lookup = new_lookup_table(base);
 
     let mut saw = if has_base_prefix { '0' } else { '_' };
-    let mut out = BigUint::default();
+    let mut digits = Vec::with_capacity(s.len());
     for (i, &c) in s.iter().enumerate() {
         if c == b'_' {
             if saw != '0' {
-                return Err(format!("bad '_' at index {i}"));
+                return Err(ParseIntError::BadUnderscore { index: offset + i });
             }
             saw = '_';
             continue;
         }
 
         match lookup[c as usize] {
-            0xff => return Err(format!("bad digit '{}' at index {i}", c as char)),
+            0xff => {
+                return Err(ParseIntError::InvalidDigit {
+                    index: offset + i,
+                    byte: c,
+                })
+            }
             d => {
-                out = out * base + d;
+                digits.push(d);
                 saw = '0';
             }
         }
     }
 
     if saw == '_' {
-        return Err(format!("bad '_' at the end"));
+        return Err(ParseIntError::BadUnderscore {
+            index: offset + s.len() - 1,
+        });
     }
 
-    Ok(out)
+    Ok(natconv::from_digits(&digits, base))
 }
 
-fn scan_disallowing_underscores(s: &[u8], base: u8) -> Result<BigUint, String> {
+/// Scans the digits `s` in `base`, where `offset` is the index of `s` within the
+/// whole input, used for error reporting.
+fn scan_disallowing_underscores(
+    s: &[u8],
+    base: u8,
+    offset: usize,
+) -> Result<BigUint, ParseIntError> {
+    if s.is_empty() {
+        return Err(ParseIntError::Empty);
+    }
+
     let lookup = new_lookup_table(base);
-    let mut out = BigUint::default();
+    let mut digits = Vec::with_capacity(s.len());
     for (i, &c) in s.iter().enumerate() {
         match lookup[c as usize] {
-            0xff => return Err(format!("bad digit '{c}' at index {i}")),
-            d => out = out * base + d,
+            0xff => {
+                return Err(ParseIntError::InvalidDigit {
+                    index: offset + i,
+                    byte: c,
+                })
+            }
+            d => digits.push(d),
         }
     }
 
-    Ok(out)
+    Ok(natconv::from_digits(&digits, base))
 }
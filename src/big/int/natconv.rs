@@ -0,0 +1,380 @@
+//! Conversion of magnitudes to and from strings, following the design of Go's
+//! natconv.go.
+//!
+//! Digits are produced a whole word at a time: the value is repeatedly divided by
+//! the largest power of the base fitting in a word, whose remainder is then split
+//! into digits with cheap word arithmetic. Large values are first split recursively
+//! by a table of divisors `bb^(leafSize * 2^k)`, so that the expensive full-width
+//! divisions act on operands of balanced sizes.
+//!
+//! Parsing runs the other way round: digits are gathered into words, which are
+//! combined pairwise by multiplying the more significant half with a cached power of
+//! the base, so that the multiplications act on operands of balanced sizes too.
+
+use std::sync::{Arc, Mutex};
+
+use num_bigint::BigUint;
+use num_integer::Integer;
+
+use super::INVERSE_LOOKUP_TABLE;
+use crate::big::MAX_BASE;
+
+/// Number of words of the blocks converted iteratively by [convert_words].
+///
+/// Blocks larger than that are split by the divisor table. Same as Go's value,
+/// which was chosen by benchmarking.
+const LEAF_SIZE: usize = 8;
+
+/// Maximum number of entries of a divisor table, which covers values of up to
+/// `LEAF_SIZE * 2^MAX_DIVISORS` words.
+const MAX_DIVISORS: usize = 64;
+
+lazy_static::lazy_static! {
+  // Divisor tables shared by all threads, indexed by base. Entries are immutable once
+  // computed, so conversions only hold the lock while extending a table.
+  static ref DIVISOR_CACHE: Vec<Mutex<Vec<Arc<Divisor>>>> =
+    (0..=MAX_BASE).map(|_| Mutex::new(vec![])).collect();
+
+  // Powers `bb^(LEAF_SIZE * 2^k)` for parsing, indexed by base like DIVISOR_CACHE.
+  static ref POWER_CACHE: Vec<Mutex<Vec<Arc<BigUint>>>> =
+    (0..=MAX_BASE).map(|_| Mutex::new(vec![])).collect();
+}
+
+/// An entry of the divisor table used to split large values.
+struct Divisor {
+    bbb: BigUint,   // divisor
+    nbits: u64,     // bit length of the divisor (discounting leading zeros) ~= log2(bbb)
+    ndigits: usize, // digit length of the divisor in terms of the output base
+}
+
+/// Returns the value of the big-endian `digits` in the given base, which must be
+/// within `[2, MAX_BASE]`. Every digit must be less than `base`.
+pub(super) fn from_digits(digits: &[u8], base: u8) -> BigUint {
+    if base.is_power_of_two() {
+        // special case: power of two bases just concatenate the bits of the digits
+        return BigUint::from_radix_be(digits, base as u32).expect("digits are below the base");
+    }
+
+    let b = base as u64;
+    let (bb, ndigits) = max_pow(b);
+
+    let leaf_digits = LEAF_SIZE * ndigits;
+    let mut k = 0;
+    while leaf_digits << (k + 1) < digits.len() {
+        k += 1;
+    }
+    let powers = powers(b, bb, k + 1);
+
+    combine_digits(digits, b, ndigits, leaf_digits, &powers)
+}
+
+/// Returns the string representation of `x` in the given base, which must be
+/// within `[2, MAX_BASE]`.
+pub(super) fn itoa(x: &BigUint, base: u8) -> Vec<u8> {
+    if x.bits() == 0 {
+        return vec![b'0'];
+    }
+
+    // allocate a buffer big enough for the result; the float estimate is off by 1
+    // at most, and the extra byte guards against rounding
+    let n = (x.bits() as f64 / (base as f64).log2()) as usize + 2;
+    let mut s = vec![0u8; n];
+
+    let b = base as u64;
+    let mut i = s.len();
+    if b.is_power_of_two() {
+        // special case: power of two bases can avoid divisions completely
+        let shift = b.trailing_zeros();
+        let mask = b - 1;
+        let mut x = x.iter_u64_digits();
+        let mut w = x.next().unwrap_or_default(); // current word
+        let mut nbits = 64u32; // number of unprocessed bits in w
+
+        // convert less-significant words (include leading zeros)
+        for xk in x {
+            // convert full digits
+            while nbits >= shift {
+                i -= 1;
+                s[i] = INVERSE_LOOKUP_TABLE[(w & mask) as usize];
+                w >>= shift;
+                nbits -= shift;
+            }
+
+            // convert any partial leading digit and advance to next word
+            if nbits == 0 {
+                // no partial digit remaining, just advance
+                w = xk;
+                nbits = 64;
+            } else {
+                // partial digit in current word w (== x[k-1]) and next word x[k]
+                w |= xk << nbits;
+                i -= 1;
+                s[i] = INVERSE_LOOKUP_TABLE[(w & mask) as usize];
+
+                // advance
+                w = xk >> (shift - nbits);
+                nbits = 64 - (shift - nbits);
+            }
+        }
+
+        // convert digits of most-significant word w (omit leading zeros)
+        while w != 0 {
+            i -= 1;
+            s[i] = INVERSE_LOOKUP_TABLE[(w & mask) as usize];
+            w >>= shift;
+        }
+    } else {
+        let (bb, ndigits) = max_pow(b);
+
+        // construct table of successive squares of bb*leafSize to use in subdivisions
+        let table = divisors(x.iter_u64_digits().len(), b, ndigits, bb);
+
+        convert_words(x.clone(), &mut s, b, ndigits, bb, &table);
+
+        // strip leading zeros
+        // (x != 0; thus s must contain at least one non-zero digit
+        // and the loop will terminate)
+        i = 0;
+        while s[i] == b'0' {
+            i += 1;
+        }
+    }
+
+    s.drain(..i);
+    s
+}
+
+/// Converts the words of `q` into digits in base `b`, writing them right-aligned into
+/// `s` and padding the front of `s` with '0's. `bb` is the largest power of `b` that
+/// fits in a word, holding `ndigits` digits, and `table` lists the divisors for the
+/// recursive splitting, which is skipped if it's empty.
+///
+/// The algorithm divides `q` by the largest entry of `table` not exceeding sqrt(q),
+/// converts the quotient and the remainder independently, and stops the recursion at
+/// blocks of at most [LEAF_SIZE] words, which are converted by repeatedly dividing by
+/// `bb`.
+fn convert_words(
+    q: BigUint,
+    s: &mut [u8],
+    b: u64,
+    ndigits: usize,
+    bb: u64,
+    table: &[Arc<Divisor>],
+) {
+    let mut q = q;
+    let mut s = s;
+
+    // split larger blocks recursively
+    if !table.is_empty() {
+        // len(q) > leafSize > 0
+        let mut index = table.len() - 1;
+        while q.iter_u64_digits().len() > LEAF_SIZE {
+            // find divisor close to sqrt(q) if possible, but in any case < q
+            let max_length = q.bits(); // ~= log2 q, or at of least largest possible q of this bit length
+            let min_length = max_length >> 1; // ~= log2 sqrt(q)
+            while index > 0 && table[index - 1].nbits > min_length {
+                index -= 1; // desired
+            }
+            if table[index].nbits >= max_length && table[index].bbb >= q {
+                index = index.checked_sub(1).expect("internal inconsistency");
+            }
+
+            // split q into the two digit number (q'*bbb + r) to form independent subblocks
+            let (qq, r) = q.div_rem(&table[index].bbb);
+
+            // convert subblocks and collect results in s[:h] and s[h:]
+            let h = s.len() - table[index].ndigits;
+            let (hi, lo) = s.split_at_mut(h);
+            convert_words(r, lo, b, ndigits, bb, &table[..index]);
+            s = hi; // == q.convertWords(s, b, ndigits, bb, table[0:index+1])
+            q = qq;
+        }
+    }
+
+    // having split any large blocks now process the remaining (small) block iteratively
+    let mut q: Vec<u64> = q.iter_u64_digits().collect();
+    let mut i = s.len();
+    while !q.is_empty() {
+        let mut r = div_w(&mut q, bb);
+        for _ in 0..ndigits {
+            if i == 0 {
+                break;
+            }
+            i -= 1;
+            s[i] = INVERSE_LOOKUP_TABLE[(r % b) as usize];
+            r /= b;
+        }
+    }
+
+    // prepend high-order zeros
+    s[..i].fill(b'0');
+}
+
+/// Returns the value of the big-endian `digits` in base `b`, where `powers[k]` is
+/// `b^(leaf_digits * 2^k)` and `bb = b^ndigits` is the largest power of `b` that fits
+/// in a word.
+///
+/// The digits are split such that the less significant part is made of
+/// `leaf_digits * 2^k` digits for the largest k leaving a non-empty more significant
+/// part, and both parts are converted recursively. Blocks of at most `leaf_digits` digits are
+/// converted iteratively a word at a time.
+fn combine_digits(
+    digits: &[u8],
+    b: u64,
+    ndigits: usize,
+    leaf_digits: usize,
+    powers: &[Arc<BigUint>],
+) -> BigUint {
+    if digits.len() <= leaf_digits {
+        // the first word takes the excess digits, the others take ndigits digits each
+        let mut out: Vec<u64> = vec![];
+        let first = match digits.len() % ndigits {
+            0 => ndigits,
+            n => n,
+        };
+        let mut chunk = &digits[..first.min(digits.len())];
+        let mut rest = &digits[chunk.len()..];
+        loop {
+            let (mut w, mut p) = (0u64, 1u64);
+            for &d in chunk {
+                w = w * b + d as u64;
+                p *= b;
+            }
+            mul_add_w(&mut out, p, w);
+
+            if rest.is_empty() {
+                break;
+            }
+            (chunk, rest) = rest.split_at(ndigits);
+        }
+
+        let out: Vec<u32> = out
+            .iter()
+            .flat_map(|&w| [w as u32, (w >> 32) as u32])
+            .collect();
+        return BigUint::new(out);
+    }
+
+    let mut k = 0;
+    while leaf_digits << (k + 1) < digits.len() {
+        k += 1;
+    }
+
+    let (hi, lo) = digits.split_at(digits.len() - (leaf_digits << k));
+    let hi = combine_digits(hi, b, ndigits, leaf_digits, &powers[..k]);
+    let lo = combine_digits(lo, b, ndigits, leaf_digits, &powers[..k]);
+
+    hi * powers[k].as_ref() + lo
+}
+
+/// Returns the table of divisors for converting an `m`-word value in base `b`, or an
+/// empty table if the value is small enough to be converted iteratively.
+fn divisors(m: usize, b: u64, ndigits: usize, bb: u64) -> Vec<Arc<Divisor>> {
+    // only compute table when x is large
+    if m <= LEAF_SIZE {
+        return vec![];
+    }
+
+    // determine k where (bb**leafSize)**(2**k) >= sqrt(x)
+    let mut k = 1;
+    let mut words = LEAF_SIZE;
+    while words < m >> 1 && k < MAX_DIVISORS {
+        k += 1;
+        words <<= 1;
+    }
+
+    // reuse and extend the cached table as appropriate
+    let mut table = DIVISOR_CACHE[b as usize]
+        .lock()
+        .unwrap_or_else(|err| err.into_inner());
+
+    // add new entries as needed
+    while table.len() < k {
+        let (mut bbb, mut ndigits) = match table.last() {
+            None => (BigUint::from(bb).pow(LEAF_SIZE as u32), ndigits * LEAF_SIZE),
+            Some(prev) => (&prev.bbb * &prev.bbb, 2 * prev.ndigits),
+        };
+
+        // optimization: exploit aggregated extra bits in macro blocks
+        let nwords = bbb.iter_u64_digits().len() as u64;
+        loop {
+            let larger = &bbb * b;
+            if larger.bits() > nwords * 64 {
+                break;
+            }
+            bbb = larger;
+            ndigits += 1;
+        }
+
+        let nbits = bbb.bits();
+        table.push(Arc::new(Divisor {
+            bbb,
+            nbits,
+            ndigits,
+        }));
+    }
+
+    table[..k].to_vec()
+}
+
+/// Divides the little-endian words `x` by `d` in place, returning the remainder and
+/// trimming leading zero words of the quotient.
+fn div_w(x: &mut Vec<u64>, d: u64) -> u64 {
+    let mut r = 0u128;
+    for w in x.iter_mut().rev() {
+        let v = (r << 64) | (*w as u128);
+        *w = (v / d as u128) as u64;
+        r = v % d as u128;
+    }
+
+    while x.last() == Some(&0) {
+        x.pop();
+    }
+
+    r as u64
+}
+
+/// Sets `x = x*y + r` for the little-endian words `x`.
+fn mul_add_w(x: &mut Vec<u64>, y: u64, r: u64) {
+    let mut c = r as u128;
+    for w in x.iter_mut() {
+        let v = (*w as u128) * (y as u128) + c;
+        *w = v as u64;
+        c = v >> 64;
+    }
+
+    if c != 0 {
+        x.push(c as u64);
+    }
+}
+
+/// Returns `[bb^LEAF_SIZE, bb^(LEAF_SIZE*2), ..., bb^(LEAF_SIZE * 2^(k-1))]` from the
+/// cache of base `b`, computing missing entries.
+fn powers(b: u64, bb: u64, k: usize) -> Vec<Arc<BigUint>> {
+    let mut table = POWER_CACHE[b as usize]
+        .lock()
+        .unwrap_or_else(|err| err.into_inner());
+
+    while table.len() < k {
+        let p = match table.last() {
+            None => BigUint::from(bb).pow(LEAF_SIZE as u32),
+            Some(prev) => prev.as_ref() * prev.as_ref(),
+        };
+        table.push(Arc::new(p));
+    }
+
+    table[..k].to_vec()
+}
+
+/// Returns `(b**n, n)` such that `b**n` is the largest power `b**n` that fits in a
+/// word.
+fn max_pow(b: u64) -> (u64, usize) {
+    let (mut p, mut n) = (b, 1); // assuming b <= MAX_BASE
+    let max = u64::MAX / b;
+    while p <= max {
+        p *= b;
+        n += 1;
+    }
+
+    (p, n)
+}
@@ -0,0 +1,272 @@
+//! Operator overloading for [Int].
+//!
+//! The arithmetic operators complement the Go-style setters such as [Int::add]: they
+//! allocate a fresh result instead of writing into a receiver. `/` and `%` implement
+//! truncated division and modulus (like Go), i.e. they agree with [Int::quo] and
+//! [Int::rem] rather than with the Euclidean [Int::div] and `Int::mod`.
+
+use std::cmp::Ordering;
+use std::ops::{
+    Add, AddAssign, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Div, DivAssign,
+    Mul, MulAssign, Neg, Not, Rem, RemAssign, Shl, ShlAssign, Shr, ShrAssign, Sub, SubAssign,
+};
+
+use num_bigint::BigInt;
+use num_traits::{Signed, ToPrimitive};
+
+use super::Int;
+
+macro_rules! forward_binop {
+    ($Op:ident, $op:ident, $OpAssign:ident, $op_assign:ident) => {
+        impl $Op<Int> for Int {
+            type Output = Int;
+
+            fn $op(self, rhs: Int) -> Int {
+                Int(self.0.$op(rhs.0))
+            }
+        }
+
+        impl $Op<&Int> for Int {
+            type Output = Int;
+
+            fn $op(self, rhs: &Int) -> Int {
+                Int(self.0.$op(&rhs.0))
+            }
+        }
+
+        impl $Op<Int> for &Int {
+            type Output = Int;
+
+            fn $op(self, rhs: Int) -> Int {
+                Int((&self.0).$op(rhs.0))
+            }
+        }
+
+        impl $Op<&Int> for &Int {
+            type Output = Int;
+
+            fn $op(self, rhs: &Int) -> Int {
+                Int((&self.0).$op(&rhs.0))
+            }
+        }
+
+        impl $OpAssign<Int> for Int {
+            fn $op_assign(&mut self, rhs: Int) {
+                self.0.$op_assign(rhs.0);
+            }
+        }
+
+        impl $OpAssign<&Int> for Int {
+            fn $op_assign(&mut self, rhs: &Int) {
+                self.0.$op_assign(&rhs.0);
+            }
+        }
+    };
+}
+
+// `BigInt` implements the arithmetic operators for primitives directly.
+macro_rules! forward_arith_primitive {
+    ($Op:ident, $op:ident, $OpAssign:ident, $op_assign:ident, $($t:ty)*) => {$(
+        impl $Op<$t> for Int {
+            type Output = Int;
+
+            fn $op(self, rhs: $t) -> Int {
+                Int(self.0.$op(rhs))
+            }
+        }
+
+        impl $Op<$t> for &Int {
+            type Output = Int;
+
+            fn $op(self, rhs: $t) -> Int {
+                Int((&self.0).$op(rhs))
+            }
+        }
+
+        impl $Op<Int> for $t {
+            type Output = Int;
+
+            fn $op(self, rhs: Int) -> Int {
+                Int(self.$op(rhs.0))
+            }
+        }
+
+        impl $Op<&Int> for $t {
+            type Output = Int;
+
+            fn $op(self, rhs: &Int) -> Int {
+                Int(self.$op(&rhs.0))
+            }
+        }
+
+        impl $OpAssign<$t> for Int {
+            fn $op_assign(&mut self, rhs: $t) {
+                self.0.$op_assign(rhs);
+            }
+        }
+    )*};
+}
+
+// The bitwise operators of `BigInt` only accept `BigInt` operands, so primitives are
+// widened first. Negative operands use two's complement semantics, just like Go.
+macro_rules! forward_bitwise_primitive {
+    ($Op:ident, $op:ident, $OpAssign:ident, $op_assign:ident, $($t:ty)*) => {$(
+        impl $Op<$t> for Int {
+            type Output = Int;
+
+            fn $op(self, rhs: $t) -> Int {
+                Int(self.0.$op(BigInt::from(rhs)))
+            }
+        }
+
+        impl $Op<$t> for &Int {
+            type Output = Int;
+
+            fn $op(self, rhs: $t) -> Int {
+                Int((&self.0).$op(BigInt::from(rhs)))
+            }
+        }
+
+        impl $Op<Int> for $t {
+            type Output = Int;
+
+            fn $op(self, rhs: Int) -> Int {
+                Int(BigInt::from(self).$op(rhs.0))
+            }
+        }
+
+        impl $Op<&Int> for $t {
+            type Output = Int;
+
+            fn $op(self, rhs: &Int) -> Int {
+                Int(BigInt::from(self).$op(&rhs.0))
+            }
+        }
+
+        impl $OpAssign<$t> for Int {
+            fn $op_assign(&mut self, rhs: $t) {
+                self.0.$op_assign(BigInt::from(rhs));
+            }
+        }
+    )*};
+}
+
+macro_rules! forward_shift {
+    ($Op:ident, $op:ident, $OpAssign:ident, $op_assign:ident, $($t:ty)*) => {$(
+        impl $Op<$t> for Int {
+            type Output = Int;
+
+            fn $op(self, rhs: $t) -> Int {
+                Int(self.0.$op(rhs))
+            }
+        }
+
+        impl $Op<$t> for &Int {
+            type Output = Int;
+
+            fn $op(self, rhs: $t) -> Int {
+                Int((&self.0).$op(rhs))
+            }
+        }
+
+        impl $OpAssign<$t> for Int {
+            fn $op_assign(&mut self, rhs: $t) {
+                self.0.$op_assign(rhs);
+            }
+        }
+    )*};
+}
+
+macro_rules! impl_cmp_primitive {
+    ($($t:ty)*) => {$(
+        impl PartialEq<$t> for Int {
+            fn eq(&self, other: &$t) -> bool {
+                cmp_i128(self, *other as i128) == Ordering::Equal
+            }
+        }
+
+        impl PartialEq<Int> for $t {
+            fn eq(&self, other: &Int) -> bool {
+                other == self
+            }
+        }
+
+        impl PartialOrd<$t> for Int {
+            fn partial_cmp(&self, other: &$t) -> Option<Ordering> {
+                Some(cmp_i128(self, *other as i128))
+            }
+        }
+
+        impl PartialOrd<Int> for $t {
+            fn partial_cmp(&self, other: &Int) -> Option<Ordering> {
+                Some(cmp_i128(other, *self as i128).reverse())
+            }
+        }
+    )*};
+}
+
+forward_binop!(Add, add, AddAssign, add_assign);
+forward_binop!(Sub, sub, SubAssign, sub_assign);
+forward_binop!(Mul, mul, MulAssign, mul_assign);
+forward_binop!(Div, div, DivAssign, div_assign);
+forward_binop!(Rem, rem, RemAssign, rem_assign);
+forward_binop!(BitAnd, bitand, BitAndAssign, bitand_assign);
+forward_binop!(BitOr, bitor, BitOrAssign, bitor_assign);
+forward_binop!(BitXor, bitxor, BitXorAssign, bitxor_assign);
+
+forward_arith_primitive!(Add, add, AddAssign, add_assign, i64 u64 u32 usize);
+forward_arith_primitive!(Sub, sub, SubAssign, sub_assign, i64 u64 u32 usize);
+forward_arith_primitive!(Mul, mul, MulAssign, mul_assign, i64 u64 u32 usize);
+forward_arith_primitive!(Div, div, DivAssign, div_assign, i64 u64 u32 usize);
+forward_arith_primitive!(Rem, rem, RemAssign, rem_assign, i64 u64 u32 usize);
+
+forward_bitwise_primitive!(BitAnd, bitand, BitAndAssign, bitand_assign, i64 u64 u32 usize);
+forward_bitwise_primitive!(BitOr, bitor, BitOrAssign, bitor_assign, i64 u64 u32 usize);
+forward_bitwise_primitive!(BitXor, bitxor, BitXorAssign, bitxor_assign, i64 u64 u32 usize);
+
+forward_shift!(Shl, shl, ShlAssign, shl_assign, u32 usize);
+forward_shift!(Shr, shr, ShrAssign, shr_assign, u32 usize);
+
+impl_cmp_primitive!(i64 u64 u32 usize);
+
+impl Neg for Int {
+    type Output = Int;
+
+    fn neg(self) -> Int {
+        Int(-self.0)
+    }
+}
+
+impl Neg for &Int {
+    type Output = Int;
+
+    fn neg(self) -> Int {
+        Int(-&self.0)
+    }
+}
+
+impl Not for Int {
+    type Output = Int;
+
+    fn not(self) -> Int {
+        Int(!self.0)
+    }
+}
+
+impl Not for &Int {
+    type Output = Int;
+
+    fn not(self) -> Int {
+        Int(!&self.0)
+    }
+}
+
+/// Compares `x` against a primitive widened to `i128`, which holds every supported
+/// primitive without loss.
+fn cmp_i128(x: &Int, y: i128) -> Ordering {
+    match x.0.to_i128() {
+        Some(v) => v.cmp(&y),
+        None if x.0.is_negative() => Ordering::Less,
+        None => Ordering::Greater,
+    }
+}
@@ -0,0 +1,453 @@
+use num_bigint::{BigInt, BigUint};
+use num_integer::Integer;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+
+use super::{jacobi, lucas::lucas_v, Int};
+use crate::primes;
+
+// The odd primes sieved with stay below this bound.
+const SIEVE_BOUND: u64 = 1 << 16;
+
+lazy_static::lazy_static! {
+    // the odd primes below SIEVE_BOUND, grouped so that the product of every
+    // group fits in a u64
+    static ref SIEVE_PRIMES: Vec<(u64, Vec<u64>)> = {
+        let mut out: Vec<(u64, Vec<u64>)> = Vec::new();
+        for p in primes::iter(3..SIEVE_BOUND) {
+            match out.last_mut() {
+                Some((m, g)) if m.checked_mul(p).is_some() => {
+                    *m *= p;
+                    g.push(p);
+                }
+                _ => out.push((p, vec![p])),
+            }
+        }
+        out
+    };
+}
+
+impl Int {
+    /// Reports whether `self` is probably prime, applying the Miller-Rabin test
+    /// with `n` pseudorandomly chosen bases as well as a Baillie-PSW test.
+    ///
+    /// If `self` is prime, `probably_prime` returns `true`.
+    /// If `self` is chosen randomly and not prime, `probably_prime` probably returns `false`.
+    /// The probability of returning `true` for a randomly chosen non-prime is at most ¼ⁿ.
+    ///
+    /// `probably_prime` is 100% accurate for inputs less than 2⁶⁴.
+    /// See Menezes et al., Handbook of Applied Cryptography, 1997, pp. 145-149,
+    /// and FIPS 186-4 Appendix F for further discussion of the error probabilities.
+    ///
+    /// `probably_prime` is not suitable for judging primes that an adversary may
+    /// have crafted to fool the test.
+    pub fn probably_prime(&self, n: usize) -> bool {
+        if !self.0.is_positive() {
+            return false;
+        }
+
+        const PRIME_BIT_MASK: u64 = (1 << 2)
+            | (1 << 3)
+            | (1 << 5)
+            | (1 << 7)
+            | (1 << 11)
+            | (1 << 13)
+            | (1 << 17)
+            | (1 << 19)
+            | (1 << 23)
+            | (1 << 29)
+            | (1 << 31)
+            | (1 << 37)
+            | (1 << 41)
+            | (1 << 43)
+            | (1 << 47)
+            | (1 << 53)
+            | (1 << 59)
+            | (1 << 61);
+
+        let v = self.0.magnitude();
+
+        let w = v.iter_u64_digits().next().unwrap_or_default();
+        if (v.bits() <= 64) && (w < 64) {
+            return (PRIME_BIT_MASK & (1 << w)) != 0;
+        }
+
+        if (w & 1) == 0 {
+            return false; // n is even
+        }
+
+        const PRIMES_A: u64 = 3 * 5 * 7 * 11 * 13 * 17 * 19 * 23 * 37;
+        const PRIMES_B: u64 = 29 * 31 * 41 * 43 * 47 * 53;
+
+        let r = (v % (PRIMES_A * PRIMES_B)).to_u64().expect("fit in u64");
+        let (r_a, r_b) = (r % PRIMES_A, r % PRIMES_B);
+
+        if (r_a % 3 == 0)
+            || (r_a % 5 == 0)
+            || (r_a % 7 == 0)
+            || (r_a % 11 == 0)
+            || (r_a % 13 == 0)
+            || (r_a % 17 == 0)
+            || (r_a % 19 == 0)
+            || (r_a % 23 == 0)
+            || (r_a % 37 == 0)
+            || (r_b % 29 == 0)
+            || (r_b % 31 == 0)
+            || (r_b % 41 == 0)
+            || (r_b % 43 == 0)
+            || (r_b % 47 == 0)
+            || (r_b % 53 == 0)
+        {
+            return false;
+        }
+
+        probably_prime_miller_rabin(v, n + 1, true) && probably_prime_lucas(v)
+    }
+
+    /// Returns the smallest probable prime greater than `self`, which is 2 for all
+    /// `self < 2`.
+    ///
+    /// The candidates are windows of odd numbers, from which the multiples of the
+    /// small primes are sieved out before the rest are tested like
+    /// `probably_prime(0)` does. Sieving by the primes below 2¹⁶ leaves 10% of the
+    /// odd numbers to test. As `probably_prime` itself leaves 27% of them after
+    /// its trial division by the primes up to 53, this is at most 2.7 times as fast
+    /// as testing the odd numbers above `self` one by one. Measured, that's about
+    /// 1.5 times at 128 to 256 bits and 2.5 times from 512 to 2048 bits.
+    pub fn next_prime(&self) -> Int {
+        let n = self.0.magnitude();
+        if !self.0.is_positive() || n.bits() < 2 {
+            return Int::new(2);
+        }
+
+        // the smallest odd number greater than n
+        let mut lo = n + if n.is_odd() { 2u8 } else { 1u8 };
+        let (width, bound) = window(n);
+        loop {
+            let composite = sieve(&lo, width, bound);
+            let found = (0..width)
+                .filter(|&i| !composite[i])
+                .map(|i| &lo + 2 * i as u64)
+                .find(is_probable_prime);
+            if let Some(p) = found {
+                return Int(p.into());
+            }
+            lo += 2 * width as u64;
+        }
+    }
+
+    /// Returns the largest probable prime less than `self`, or `None` if `self ≤ 2`.
+    /// See [Int::next_prime].
+    pub fn prev_prime(&self) -> Option<Int> {
+        let n = self.0.magnitude();
+        if !self.0.is_positive() || n.bits() < 2 {
+            return None;
+        }
+        match n.to_u64() {
+            Some(2) => return None,
+            Some(3) => return Some(Int::new(2)),
+            _ => {}
+        }
+
+        // the largest odd number less than n, which is at least 3
+        let mut hi = n - if n.is_odd() { 2u8 } else { 1u8 };
+        let (width, bound) = window(n);
+        loop {
+            // the window ends at 3, which is prime and so ends the search
+            let width = hi
+                .to_u64()
+                .map_or(width, |hi| width.min(((hi - 3) / 2 + 1) as usize));
+            let lo = &hi - 2 * (width as u64 - 1);
+
+            let composite = sieve(&lo, width, bound);
+            let found = (0..width)
+                .rev()
+                .filter(|&i| !composite[i])
+                .map(|i| &lo + 2 * i as u64)
+                .find(is_probable_prime);
+            if let Some(p) = found {
+                return Some(Int(p.into()));
+            }
+            hi = lo - 2u8;
+        }
+    }
+}
+
+/// Returns the number of odd candidates to sieve at once around `n` and the bound
+/// on the primes to sieve them with. About ln(n)/2 odd numbers lie between two
+/// primes, so a window spans a few such gaps, and the larger `n` is, the more
+/// primality tests are saved by sieving with more primes. Neither 4 times wider
+/// windows nor 20 times lower bounds change the running time of
+/// [Int::next_prime] beyond noise from 128 to 2048 bits.
+fn window(n: &BigUint) -> (usize, u64) {
+    let bits = n.bits();
+    (
+        (bits as usize).clamp(64, 1 << 12),
+        (bits * bits).clamp(1 << 8, SIEVE_BOUND),
+    )
+}
+
+/// Marks the odd numbers `lo + 2i` for `i < width` that have an odd prime below
+/// `bound` as a proper factor. `lo` must be odd.
+fn sieve(lo: &BigUint, width: usize, bound: u64) -> Vec<bool> {
+    let mut composite = vec![false; width];
+    let small = lo.to_u64();
+
+    for (m, group) in SIEVE_PRIMES.iter().take_while(|(_, g)| g[0] < bound) {
+        let r = (lo % *m).to_u64().expect("fit in u64");
+        for &p in group {
+            // lo + 2i ≡ 0 (mod p) for i ≡ -r/2, where 1/2 ≡ (p+1)/2
+            let mut i = ((p - r % p) % p * p.div_ceil(2) % p) as usize;
+            if small.and_then(|lo| lo.checked_add(2 * i as u64)) == Some(p) {
+                i += p as usize;
+            }
+            while i < width {
+                composite[i] = true;
+                i += p as usize;
+            }
+        }
+    }
+
+    composite
+}
+
+/// Reports whether `n` is probably prime as by `probably_prime(0)`.
+fn is_probable_prime(n: &BigUint) -> bool {
+    match n.to_u64() {
+        Some(n) => primes::is_prime_u64(n),
+        None => probably_prime_miller_rabin(n, 1, true) && probably_prime_lucas(n),
+    }
+}
+
+/// Reports whether `n` passes `reps` rounds of the Miller-Rabin primality test,
+/// using pseudo-randomly chosen bases. If `force2` is `true`, one of the rounds
+/// is forced to use base 2.
+/// See Handbook of Applied Cryptography, p. 139, Algorithm 4.24.
+/// The number `n` is known to be non-zero.
+pub(crate) fn probably_prime_miller_rabin(n: &BigUint, reps: usize, force2: bool) -> bool {
+    let one = BigUint::one();
+    let two = BigUint::from(2u8);
+
+    let nm1 = n - &one;
+    // determine q, k such that nm1 = q << k
+    let k = nm1.trailing_zeros().unwrap_or_default();
+    let q = &nm1 >> k;
+
+    let nm3 = &nm1 - &two;
+    let mut rand = SplitMix64(n.iter_u64_digits().next().unwrap_or_default());
+
+    'next_random: for i in 0..reps {
+        let x = if (i == reps - 1) && force2 {
+            two.clone()
+        } else {
+            rand.below(&nm3) + &two
+        };
+
+        let mut y = x.modpow(&q, n);
+        if (y == one) || (y == nm1) {
+            continue;
+        }
+
+        for _ in 1..k {
+            y = (&y * &y) % n;
+            if y == nm1 {
+                continue 'next_random;
+            }
+            if y == one {
+                return false;
+            }
+        }
+
+        return false;
+    }
+
+    true
+}
+
+/// Reports whether `n` passes the "almost extra strong" Lucas probable prime test,
+/// using Baillie-OEIS parameter selection. This corresponds to "AESLPSP" on Jacobsen's
+/// tables (link below). The combination of this test and a Miller-Rabin/Fermat test with
+/// base 2 gives a Baillie-PSW test.
+///
+/// References:
+///
+/// Baillie and Wagstaff, "Lucas Pseudoprimes", Mathematics of Computation 35(152),
+/// October 1980, pp. 1391-1417, especially page 1401.
+/// <https://www.ams.org/journals/mcom/1980-35-152/S0025-5718-1980-0583518-6/S0025-5718-1980-0583518-6.pdf>
+///
+/// Grantham, "Frobenius Pseudoprimes", Mathematics of Computation 70(234),
+/// March 2000, pp. 873-891.
+/// <https://www.ams.org/journals/mcom/2001-70-234/S0025-5718-00-01197-2/S0025-5718-00-01197-2.pdf>
+///
+/// Baillie, "Extra strong Lucas pseudoprimes", OEIS A217719, <https://oeis.org/A217719>.
+///
+/// Jacobsen, "Pseudoprime Statistics, Tables, and Data", <http://ntheory.org/pseudoprimes.html>.
+///
+/// Nicely, "The Baillie-PSW Primality Test", <https://web.archive.org/web/20191121062007/http://www.trnicely.net/misc/bpsw.html>.
+/// (Note that Nicely's definition of AESLPSP is actually ESLPSP, as noted by Jacobsen.)
+///
+/// Crandall and Pomerance, Prime Numbers: A Computational Perspective, 2nd ed.
+/// Springer, 2005.
+pub(crate) fn probably_prime_lucas(n: &BigUint) -> bool {
+    let one = BigUint::one();
+    let two = BigUint::from(2u8);
+
+    // Discard 0, 1.
+    if n.is_zero() || (n == &one) {
+        return false;
+    }
+    // Two is the only even prime.
+    if n.is_even() {
+        return n == &two;
+    }
+
+    // Baillie-OEIS "method C" for choosing D, P, Q,
+    // as in https://oeis.org/A217719/a217719.txt:
+    // try increasing P ≥ 3 such that D = P² - 4 (so Q = 1)
+    // until Jacobi(D, n) = -1.
+    // The search is expected to succeed for non-square n after just a few trials.
+    // After more than expected failures, check whether n is square
+    // (which would cause Jacobi(D, n) = 1 for all D not dividing n).
+    let int_n = Int(BigInt::from(n.clone()));
+    let mut p = 3u64;
+    loop {
+        assert!(
+            p <= 10000,
+            "math::big: internal error: cannot find (D/n) = -1 for {int_n}"
+        );
+
+        let d = Int(BigInt::from(p * p - 4));
+        match jacobi(&d, &int_n) {
+            -1 => break,
+            0 => {
+                // d = p²-4 = (p-2)(p+2).
+                // If (d/n) == 0 then d shares a prime factor with n.
+                // Since the loop proceeds in increasing p and starts with p-2==1,
+                // the shared prime factor must be p+2.
+                // If p+2 == n, then n is prime; otherwise p+2 is a proper factor of n.
+                return n == &BigUint::from(p + 2);
+            }
+            _ => {}
+        }
+
+        if p == 40 {
+            // We'll never find (d/n) = -1 if n is a square.
+            // If n is a non-square we expect to find a d in just a few attempts on average.
+            // After 40 attempts, take a moment to check if n is indeed a square.
+            let t = n.sqrt();
+            if &(&t * &t) == n {
+                return false;
+            }
+        }
+
+        p += 1;
+    }
+
+    // Grantham definition of "extra strong Lucas pseudoprime", after Thm 2.3 on p. 876
+    // (D, P, Q above have become Δ, b, 1):
+    //
+    // Let U_n = U_n(b, 1), V_n = V_n(b, 1), and Δ = b²-4.
+    // An extra strong Lucas pseudoprime to base b is a composite n = 2^r s + Jacobi(Δ, n),
+    // where s is odd and gcd(n, 2*Δ) = 1, such that either (i) U_s ≡ 0 mod n and V_s ≡ ±2 mod n,
+    // or (ii) V_{2^t s} ≡ 0 mod n for some 0 ≤ t < r-1.
+    //
+    // We know gcd(n, Δ) = 1 or else we'd have found Jacobi(d, n) == 0 above.
+    // We know gcd(n, 2) = 1 because n is odd.
+    //
+    // Arrange s = (n - Jacobi(Δ, n)) / 2^r = (n+1) / 2^r.
+    let s = n + &one;
+    let r = s.trailing_zeros().unwrap_or_default();
+    let s = s >> r;
+    let nm2 = n - &two; // n-2
+
+    // We apply the "almost extra strong" test, which checks the above conditions
+    // except for U_s ≡ 0 mod n, which allows us to avoid computing any U_k values.
+    //
+    // Compute Lucas sequence V_s(b, 1), where:
+    //
+    //     V(0) = 2
+    //     V(1) = P
+    //     V(k) = P V(k-1) - Q V(k-2).
+    //
+    // (Remember that due to method C above, P = b, Q = 1.)
+    let p = BigUint::from(p);
+    let (vk, vk1) = lucas_v(&s, &p, 1, Some(n));
+
+    // Now k=s, so vk = V(s). Check V(s) ≡ ±2 (mod n).
+    if (vk == two) || (vk == nm2) {
+        // Check U(s) ≡ 0.
+        // As suggested by Jacobsen, apply Crandall and Pomerance equation 3.13:
+        //
+        //     U(k) = D⁻¹ (2 V(k+1) - P V(k))
+        //
+        // Since we are checking for U(k) == 0 it suffices to check 2 V(k+1) == P V(k) mod n,
+        // or P V(k) - 2 V(k+1) == 0 mod n.
+        let t1 = (&vk * &p) % n;
+        let t2 = (&vk1 << 1) % n;
+        if t1 == t2 {
+            return true;
+        }
+    }
+
+    // Check V(2^t s) ≡ 0 mod n for some 0 ≤ t < r-1.
+    let mut vk = vk;
+    for _ in 0..r.saturating_sub(1) {
+        if vk.is_zero() {
+            return true;
+        }
+        // Optimization: V(k) = 2 is a fixed point for V(k') = V(k)² - 2,
+        // so if V(k) = 2, we can stop: we will never find a future V(k) == 0.
+        if vk == two {
+            return false;
+        }
+        // k' = 2k
+        // V(k') = V(2k) = V(k)² - 2
+        vk = (&vk * &vk + &nm2) % n;
+    }
+
+    false
+}
+
+/// A tiny deterministic PRNG (SplitMix64) used to pick the Miller-Rabin bases,
+/// the ECM curves and the SIQS polynomials, seeded from the number under test
+/// just as Go does.
+pub(super) struct SplitMix64(pub(super) u64);
+
+impl SplitMix64 {
+    pub(super) fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    /// Returns a uniformly distributed value in `[0, limit)`.
+    pub(super) fn below(&mut self, limit: &BigUint) -> BigUint {
+        let bits = limit.bits();
+        let words = ((bits - 1) / 64 + 1) as usize;
+        let top_mask = match bits % 64 {
+            0 => u64::MAX,
+            m => (1 << m) - 1,
+        };
+
+        loop {
+            let mut digits: Vec<u64> = (0..words).map(|_| self.next_u64()).collect();
+            if let Some(top) = digits.last_mut() {
+                *top &= top_mask;
+            }
+
+            let v = from_u64_digits(&digits);
+            if &v < limit {
+                return v;
+            }
+        }
+    }
+}
+
+pub(super) fn from_u64_digits(digits: &[u64]) -> BigUint {
+    let u32s: Vec<u32> = digits
+        .iter()
+        .flat_map(|&d| [d as u32, (d >> 32) as u32])
+        .collect();
+    BigUint::new(u32s)
+}
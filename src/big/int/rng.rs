@@ -0,0 +1,33 @@
+//! [RngCore] support for [Int](super::Int), available with the `rand_core` feature.
+
+use std::io::{self, Read};
+
+use rand_core::RngCore;
+
+/// Adapts a [RngCore] to the [Read] taken by [Int::rand], [Int::rand_bits] and
+/// [Int::rand_range] as their source of randomness, e.g.
+///
+/// ```
+/// use math::big::{Int, RngReader};
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let mut rng = StdRng::seed_from_u64(1);
+/// let n = Int::new(1000);
+/// let mut x = Int::default();
+/// x.rand(&mut RngReader(&mut rng), &n);
+/// assert!(x >= Int::default() && x < n);
+/// ```
+///
+/// [Int::rand]: super::Int::rand
+/// [Int::rand_bits]: super::Int::rand_bits
+/// [Int::rand_range]: super::Int::rand_range
+#[derive(Clone, Debug)]
+pub struct RngReader<R>(pub R);
+
+impl<R: RngCore> Read for RngReader<R> {
+    /// Fills all of `buf` by [RngCore::try_fill_bytes].
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.try_fill_bytes(buf)?;
+        Ok(buf.len())
+    }
+}
@@ -0,0 +1,152 @@
+use num_bigint::{BigInt, BigUint};
+use num_traits::{Signed, ToPrimitive};
+
+use super::Int;
+use crate::primes::{is_prime_u64, pow_mod};
+
+impl Int {
+    /// Reports whether `self` is a perfect power `bᵉ` for some integers `b` and
+    /// `e ≥ 2`, returning `Some((b, e))` with the largest such `e` if so. Negative
+    /// values are perfect powers of negative bases with odd exponents only. 0, 1 and
+    /// -1 are powers with any exponent, so they aren't reported as perfect powers.
+    pub fn is_perfect_power(&self) -> Option<(Int, u32)> {
+        let (b, e) = perfect_power(self.0.magnitude())?;
+        if !self.0.is_negative() {
+            return Some((Int(b.into()), e));
+        }
+
+        // (-b)^e == -(b^e) needs an odd e, so move the factors of 2 into the base
+        let (mut b, mut e) = (b, e);
+        while e & 1 == 0 {
+            b = &b * &b;
+            e /= 2;
+        }
+        if e == 1 {
+            return None;
+        }
+
+        Some((Int(-BigInt::from(b)), e))
+    }
+
+    /// Reports whether `self` is the square of an integer. Most non-squares are
+    /// rejected by quadratic residue tests modulo 64, 63, 65 and 11 before the
+    /// square root is computed.
+    pub fn is_perfect_square(&self) -> bool {
+        if self.0.is_negative() {
+            return false;
+        }
+
+        is_square(self.0.magnitude())
+    }
+
+    /// Sets `self` to `⌊ⁿ√x⌋`, the largest integer such that `selfⁿ ≤ x`, and returns
+    /// `self`. For negative `x` and odd `n`, `self` is set to `-⌊ⁿ√|x|⌋`, i.e. the root
+    /// is truncated toward zero.
+    ///
+    /// # Panics
+    /// If `n` is 0, or `x` is negative and `n` is even.
+    pub fn root(&mut self, x: &Self, n: u32) -> &mut Self {
+        assert!(n != 0, "root degree mustn't be 0");
+        assert!(
+            n % 2 == 1 || !x.0.is_negative(),
+            "even root of a negative number"
+        );
+
+        self.0 = x.0.nth_root(n);
+        self
+    }
+}
+
+/// Reports whether `x` is a square, see [Int::is_perfect_square].
+pub(crate) fn is_square(x: &BigUint) -> bool {
+    if !is_square_candidate(x) {
+        return false;
+    }
+
+    let s = x.sqrt();
+    &s * &s == *x
+}
+
+/// Rejects non-squares `x` whose residues modulo 64, 63, 65 or 11 aren't quadratic
+/// residues. Only 12/64 * 16/63 * 21/65 * 6/11 ≈ 0.8% of non-squares pass.
+fn is_square_candidate(x: &BigUint) -> bool {
+    // bit i of QRm is set iff i is a quadratic residue modulo m
+    const QR64: u64 = 0x0202_0212_0203_0213;
+    const QR63: u64 = 0x0402_4830_1245_0293;
+    const QR65: u128 = 0x0001_218a_0198_6601_4613;
+    const QR11: u16 = 0x023b;
+
+    let w = x.iter_u64_digits().next().unwrap_or_default();
+    if QR64 & (1 << (w % 64)) == 0 {
+        return false;
+    }
+
+    let r = (x % (63u64 * 65 * 11)).to_u64().expect("fit in u64");
+    (QR63 & (1 << (r % 63)) != 0) && (QR65 & (1 << (r % 65)) != 0) && (QR11 & (1 << (r % 11)) != 0)
+}
+
+/// Returns `(b, e)` such that `x = bᵉ` with the largest `e ≥ 2`, or `None` if there
+/// is no such `e` or `x < 2`.
+pub(super) fn perfect_power(x: &BigUint) -> Option<(BigUint, u32)> {
+    if x.bits() < 2 {
+        return None;
+    }
+
+    // Every exponent of a perfect power is a multiple of some prime p, and the root
+    // is at least 2, so p <= log2(x). Once x = bᵖ is found, b is examined for the
+    // same p again, which collects the largest exponent as a product of primes.
+    let (mut b, mut e) = (x.clone(), 1u32);
+    let mut p = 2u32;
+    while (p as u64) < b.bits() {
+        if is_power_candidate(&b, p) {
+            let r = if p == 2 { b.sqrt() } else { b.nth_root(p) };
+            if r.pow(p) == b {
+                b = r;
+                e *= p;
+                continue;
+            }
+        }
+
+        p = next_prime(p);
+    }
+
+    if e == 1 {
+        None
+    } else {
+        Some((b, e))
+    }
+}
+
+/// Rejects most `x` that aren't `p`-th powers for prime `p`, by checking that `x`
+/// is a `p`-th power residue modulo a few primes `q ≡ 1 (mod p)`.
+fn is_power_candidate(x: &BigUint, p: u32) -> bool {
+    if p == 2 {
+        return is_square_candidate(x);
+    }
+
+    // p-th powers are a (q-1)/p fraction of the units modulo q, so each q passes
+    // only about 1/p of the non-powers
+    let mut checked = 0;
+    let mut q = 2 * p as u64 + 1;
+    while checked < 4 && q <= u32::MAX as u64 {
+        if is_prime_u64(q) {
+            let r = (x % q).to_u64().expect("fit in u64");
+            if r != 0 && pow_mod(r, (q - 1) / p as u64, q) != 1 {
+                return false;
+            }
+            checked += 1;
+        }
+        q += 2 * p as u64;
+    }
+
+    true
+}
+
+/// Returns the smallest prime greater than `p` for `p` >= 2.
+fn next_prime(p: u32) -> u32 {
+    let mut n = p + 1;
+    while !is_prime_u64(n as u64) {
+        n += 1;
+    }
+    n
+}
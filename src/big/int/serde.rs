@@ -0,0 +1,227 @@
+//! [Serialize] and [Deserialize] support for [Int], available with the `serde` feature.
+//!
+//! Human-readable formats such as JSON or TOML see an [Int] as a decimal string, and
+//! also accept plain integers when deserializing. Compact formats such as bincode see
+//! a tuple of the sign (-1, 0 or +1, as an `i8`) and the big-endian bytes of the
+//! absolute value, as returned by [Int::bytes].
+//!
+//! The [decimal] and [hex] modules fix the representation of a field regardless of the
+//! format, e.g.
+//!
+//! ```ignore
+//! #[derive(Serialize, Deserialize)]
+//! struct Key {
+//!     #[serde(with = "math::big::serde::hex")]
+//!     modulus: Int,
+//! }
+//! ```
+
+use std::fmt;
+
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::ser::{SerializeTuple, Serializer};
+use serde::{Deserialize, Serialize};
+
+use super::Int;
+
+impl Serialize for Int {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            return serializer.collect_str(self);
+        }
+
+        let mut t = serializer.serialize_tuple(2)?;
+        t.serialize_element(&(self.sign() as i8))?;
+        t.serialize_element(&Bytes(&self.bytes()))?;
+        t.end()
+    }
+}
+
+impl<'de> Deserialize<'de> for Int {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(IntVisitor { base: 10 })
+        } else {
+            deserializer.deserialize_tuple(2, SignMagnitudeVisitor)
+        }
+    }
+}
+
+/// Serializes an [Int] as a decimal string in every format.
+pub mod decimal {
+    use serde::{Deserializer, Serializer};
+
+    use super::{Int, IntVisitor};
+
+    pub fn serialize<S: Serializer>(x: &Int, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(x)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Int, D::Error> {
+        deserializer.deserialize_str(IntVisitor { base: 10 })
+    }
+}
+
+/// Serializes an [Int] as a hexadecimal string with a "0x" prefix, e.g. "-0x1f", in
+/// every format. The prefix is optional when deserializing.
+pub mod hex {
+    use serde::{Deserializer, Serializer};
+
+    use super::{Int, IntVisitor};
+
+    pub fn serialize<S: Serializer>(x: &Int, serializer: S) -> Result<S::Ok, S::Error> {
+        let s = x.text(16);
+        match s.strip_prefix('-') {
+            Some(abs) => serializer.collect_str(&format_args!("-0x{abs}")),
+            None => serializer.collect_str(&format_args!("0x{s}")),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Int, D::Error> {
+        deserializer.deserialize_str(IntVisitor { base: 16 })
+    }
+}
+
+/// Serializes as a byte string rather than as a sequence of `u8`.
+struct Bytes<'a>(&'a [u8]);
+
+impl Serialize for Bytes<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_bytes(self.0)
+    }
+}
+
+/// Deserializes a byte string, or a sequence of `u8` for formats lacking byte strings.
+struct ByteBuf(Vec<u8>);
+
+impl<'de> Deserialize<'de> for ByteBuf {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ByteBufVisitor;
+
+        impl<'de> Visitor<'de> for ByteBufVisitor {
+            type Value = ByteBuf;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a byte string")
+            }
+
+            fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<ByteBuf, E> {
+                Ok(ByteBuf(v.to_vec()))
+            }
+
+            fn visit_byte_buf<E: de::Error>(self, v: Vec<u8>) -> Result<ByteBuf, E> {
+                Ok(ByteBuf(v))
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<ByteBuf, A::Error> {
+                let mut out = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(b) = seq.next_element()? {
+                    out.push(b);
+                }
+                Ok(ByteBuf(out))
+            }
+        }
+
+        deserializer.deserialize_byte_buf(ByteBufVisitor)
+    }
+}
+
+/// Parses strings in `base`, where base 16 takes an optional "0x" prefix. Integers
+/// are accepted as is.
+struct IntVisitor {
+    base: u8,
+}
+
+impl<'de> Visitor<'de> for IntVisitor {
+    type Value = Int;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.base {
+            16 => f.write_str("a hexadecimal integer string"),
+            _ => f.write_str("an integer or a decimal integer string"),
+        }
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Int, E> {
+        Ok(Int::from(v))
+    }
+
+    fn visit_i128<E: de::Error>(self, v: i128) -> Result<Int, E> {
+        Ok(Int::from(v))
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Int, E> {
+        Ok(Int::from(v))
+    }
+
+    fn visit_u128<E: de::Error>(self, v: u128) -> Result<Int, E> {
+        Ok(Int::from(v))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Int, E> {
+        let (neg, abs) = match v.strip_prefix('-') {
+            Some(abs) => (true, abs),
+            None => (false, v.strip_prefix('+').unwrap_or(v)),
+        };
+        let abs = match self.base {
+            16 => abs
+                .strip_prefix("0x")
+                .or_else(|| abs.strip_prefix("0X"))
+                .unwrap_or(abs),
+            _ => abs,
+        };
+
+        // A sign left in `abs` (e.g. "--1") is rejected as a bad digit.
+        if abs.starts_with(['+', '-']) {
+            return Err(E::invalid_value(de::Unexpected::Str(v), &self));
+        }
+
+        let mut out = Int::default();
+        out.set_string(abs, self.base)
+            .map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))?;
+        if neg {
+            out.neg(&out.clone());
+        }
+
+        Ok(out)
+    }
+}
+
+/// Reads the `(sign, magnitude)` tuple of compact formats.
+struct SignMagnitudeVisitor;
+
+impl<'de> Visitor<'de> for SignMagnitudeVisitor {
+    type Value = Int;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a sign and the big-endian bytes of the magnitude")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Int, A::Error> {
+        let sign: i8 = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(0, &self))?;
+        let ByteBuf(abs) = seq
+            .next_element()?
+            .ok_or_else(|| de::Error::invalid_length(1, &self))?;
+
+        let mut out = Int::default();
+        out.set_bytes(&abs);
+
+        match (sign, out.sign()) {
+            (0, 0) | (1, 1) => Ok(out),
+            (-1, 1) => {
+                out.neg(&out.clone());
+                Ok(out)
+            }
+            (-1 | 1, 0) => Err(de::Error::invalid_value(
+                de::Unexpected::Signed(sign as i64),
+                &"a sign of 0 for a zero magnitude",
+            )),
+            _ => Err(de::Error::invalid_value(
+                de::Unexpected::Signed(sign as i64),
+                &"a sign of -1 or +1 for a non-zero magnitude",
+            )),
+        }
+    }
+}
@@ -5,6 +5,7 @@
 //! Int    signed integers
 //! ```
 //!
+mod accuracy;
 //mod arith;
 mod int;
 //mod nat;
@@ -12,6 +13,8 @@ mod int;
 /// The largest number base accepted for string conversions.
 pub const MAX_BASE: u8 = 10 + (b'z' - b'a' + 1) + (b'Z' - b'A' + 1);
 
+pub use accuracy::Accuracy;
 pub use int::*;
 
-//pub type Word = usize;
+/// A Word represents a single digit of a multi-precision unsigned integer.
+pub type Word = usize;
@@ -1,2 +1,3 @@
 pub mod big;
 pub mod bits;
+pub mod primes;
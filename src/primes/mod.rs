@@ -0,0 +1,189 @@
+//! Prime numbers up to 2⁶⁴.
+//!
+//! [iter] walks through the primes of a range by a segmented sieve of
+//! Eratosthenes, [prime_pi] counts them without listing them, [nth_prime] finds
+//! the n-th one and [is_prime_u64] tests a single number.
+
+use std::ops::RangeBounds;
+
+mod sieve;
+
+pub use sieve::Primes;
+
+// Miller-Rabin with these bases is correct for all n < 2⁶⁴, as found by J. Sinclair.
+const MILLER_RABIN_BASES: [u64; 7] = [2, 325, 9375, 28178, 450775, 9780504, 1795265022];
+
+// nth_prime counts up from 0 instead of estimating up to this index.
+const NTH_PRIME_DIRECT: u64 = 1 << 16;
+
+/// Reports whether `n` is prime. It tries the primes below 64 and then applies
+/// the Miller-Rabin test with a fixed set of bases, which is deterministic for
+/// all 64-bit integers.
+pub fn is_prime_u64(n: u64) -> bool {
+    const SMALL: [u64; 18] = [
+        2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61,
+    ];
+    for p in SMALL {
+        if n.is_multiple_of(p) {
+            return n == p;
+        }
+    }
+    if n < 64 * 64 {
+        return n > 1;
+    }
+
+    let (s, d) = (
+        (n - 1).trailing_zeros(),
+        (n - 1) >> (n - 1).trailing_zeros(),
+    );
+    'next_base: for a in MILLER_RABIN_BASES {
+        let a = a % n;
+        if a == 0 {
+            continue;
+        }
+
+        let mut x = pow_mod(a, d, n);
+        if x == 1 || x == n - 1 {
+            continue;
+        }
+        for _ in 1..s {
+            x = mul_mod(x, x, n);
+            if x == n - 1 {
+                continue 'next_base;
+            }
+        }
+        return false;
+    }
+
+    true
+}
+
+/// Returns the primes in `range` in increasing order, see [Primes].
+///
+/// ```
+/// let p: Vec<u64> = math::primes::iter(10..30).collect();
+/// assert_eq!(p, [11, 13, 17, 19, 23, 29]);
+/// ```
+pub fn iter<R: RangeBounds<u64>>(range: R) -> Primes {
+    Primes::new(range)
+}
+
+/// Returns the `n`-th prime, counting from `nth_prime(1)` = 2. Large `n` start
+/// from a lower bound `x` of the `n`-th prime, so that only the primes from `x`
+/// on are sieved after [prime_pi] counts those below.
+///
+/// # Panics
+/// If `n` is 0.
+pub fn nth_prime(n: u64) -> u64 {
+    assert!(n > 0, "there is no 0th prime");
+
+    let (mut count, from) = if n < NTH_PRIME_DIRECT {
+        (0, 0)
+    } else {
+        // p_n > n (ln n + ln ln n - 1) for n >= 2, by P. Dusart, "The kth prime is
+        // greater than k(ln k + ln ln k - 1) for k >= 2", Mathematics of
+        // Computation 68(225), 1999, pp. 411-415.
+        let ln = (n as f64).ln();
+        let x = (n as f64 * (ln + ln.ln() - 1.0)) as u64;
+        (prime_pi(x), x + 1)
+    };
+
+    for p in iter(from..) {
+        count += 1;
+        if count == n {
+            return p;
+        }
+    }
+
+    unreachable!("there are fewer than {n} primes below 2⁶⁴")
+}
+
+/// Returns π(n), the number of primes `≤ n`, in `O(n^¾)` time and `O(√n)` space.
+///
+/// The count follows the algorithm posted by Lucy_Hedgehog on Project Euler: for
+/// every `v = n / i`, `S(v)` starts out as the number of integers in `[2, v]`,
+/// and each prime `p ≤ √n` takes away the integers whose smallest prime factor
+/// is `p`, that is `S(v) -= S(v / p) - S(p - 1)` for all `v ≥ p²`.
+pub fn prime_pi(n: u64) -> u64 {
+    if n < 2 {
+        return 0;
+    }
+
+    let r = isqrt(n);
+    // small[v] = S(v) for v <= r and large[i] = S(n / i) for i <= r
+    let mut small: Vec<u64> = (0..=r).map(|v| v.saturating_sub(1)).collect();
+    let mut large: Vec<u64> = (0..=r).map(|i| n.checked_div(i).unwrap_or(1) - 1).collect();
+
+    for p in 2..=r {
+        let below = small[p as usize - 1];
+        if small[p as usize] == below {
+            continue; // not a prime
+        }
+
+        let p2 = p * p;
+        let inv = 1.0 / p as f64;
+        for i in 1..=r.min(n / p2) {
+            let d = i * p;
+            let s = if d <= r {
+                large[d as usize]
+            } else {
+                small[div(n / i, p, inv) as usize]
+            };
+            large[i as usize] -= s - below;
+        }
+
+        if p2 <= r {
+            for v in (p2..=r).rev() {
+                small[v as usize] -= small[div(v, p, inv) as usize] - below;
+            }
+        }
+    }
+
+    large[1]
+}
+
+/// Returns `a / p` for `inv` = 1/p. Below 2⁵², a floating point estimate that is
+/// off by one at most is cheaper than the integer division.
+fn div(a: u64, p: u64, inv: f64) -> u64 {
+    if a >= 1 << 52 {
+        return a / p;
+    }
+
+    let q = (a as f64 * inv) as u64;
+    if q * p > a {
+        q - 1
+    } else if (q + 1) * p <= a {
+        q + 1
+    } else {
+        q
+    }
+}
+
+/// Returns ⌊√n⌋.
+pub(crate) fn isqrt(n: u64) -> u64 {
+    let mut r = (n as f64).sqrt() as u64;
+    while r.checked_mul(r).is_none_or(|r2| r2 > n) {
+        r -= 1;
+    }
+    while (r + 1).checked_mul(r + 1).is_some_and(|r2| r2 <= n) {
+        r += 1;
+    }
+    r
+}
+
+fn mul_mod(x: u64, y: u64, m: u64) -> u64 {
+    (x as u128 * y as u128 % m as u128) as u64
+}
+
+/// Returns `x^y mod m`.
+pub(crate) fn pow_mod(x: u64, mut y: u64, m: u64) -> u64 {
+    let (mut x, mut out) = (x % m, 1 % m);
+    while y != 0 {
+        if y & 1 == 1 {
+            out = mul_mod(out, x, m);
+        }
+        x = mul_mod(x, x, m);
+        y >>= 1;
+    }
+    out
+}
@@ -0,0 +1,186 @@
+use std::ops::{Bound, RangeBounds};
+
+use super::{is_prime_u64, isqrt};
+
+// A segment holds this many bytes, one bit for each odd number, so that it
+// stays in the L1 cache while it is sieved.
+const SEGMENT_BYTES: usize = 1 << 15;
+
+// The odd numbers of a segment.
+const SEGMENT_BITS: u64 = 8 * SEGMENT_BYTES as u64;
+
+// The primes sieved with stay below this bound. Numbers beyond its square that
+// survive the sieve are confirmed by is_prime_u64.
+const SIEVING_LIMIT: u64 = 1 << 24;
+
+/// An iterator over the primes of a range in increasing order, returned by
+/// [iter](super::iter).
+///
+/// The range is sieved in segments of 2¹⁹ numbers, which are crossed off by the
+/// odd primes up to the square root of the segment's end. Those sieving primes
+/// are only collected as far as the range needs them, and at most up to 2²⁴:
+/// beyond 2⁴⁸, the numbers that survive the sieve are tested by
+/// [is_prime_u64](super::is_prime_u64).
+#[derive(Debug, Clone)]
+pub struct Primes {
+    // the odd number the next segment starts at, or None once the range is done
+    next: Option<u64>,
+    last: u64,
+    two: bool,
+    sieving: Vec<u64>,
+    sieving_bound: u64,
+    // bit i of the segment stands for start + 2i
+    segment: Vec<u64>,
+    start: u64,
+    word: usize,
+    bits: u64,
+    confirm: bool,
+}
+
+impl Primes {
+    pub(super) fn new<R: RangeBounds<u64>>(range: R) -> Self {
+        let lo = match range.start_bound() {
+            Bound::Included(&lo) => Some(lo),
+            Bound::Excluded(&lo) => lo.checked_add(1),
+            Bound::Unbounded => Some(0),
+        };
+        let last = match range.end_bound() {
+            Bound::Included(&hi) => Some(hi),
+            Bound::Excluded(&hi) => hi.checked_sub(1),
+            Bound::Unbounded => Some(u64::MAX),
+        };
+
+        let (next, last, two) = match (lo, last) {
+            (Some(lo), Some(last)) if lo <= last => ((lo.max(3) | 1), last, lo <= 2 && 2 <= last),
+            _ => (1, 0, false),
+        };
+
+        Self {
+            next: Some(next).filter(|&n| n <= last),
+            last,
+            two,
+            sieving: Vec::new(),
+            sieving_bound: 1,
+            segment: Vec::with_capacity(SEGMENT_BYTES / 8),
+            start: 0,
+            word: 0,
+            bits: 0,
+            confirm: false,
+        }
+    }
+
+    /// Sieves the next segment, and reports whether there is one.
+    fn next_segment(&mut self) -> bool {
+        let Some(start) = self.next else {
+            return false;
+        };
+        let count = ((self.last - start) / 2 + 1).min(SEGMENT_BITS);
+        let end = start + 2 * (count - 1);
+        self.next = end.checked_add(2).filter(|&n| n <= self.last);
+
+        let root = isqrt(end);
+        if root > self.sieving_bound && self.sieving_bound < SIEVING_LIMIT {
+            self.grow_sieving(root.max(2 * self.sieving_bound).min(SIEVING_LIMIT));
+        }
+        self.confirm = root > self.sieving_bound;
+
+        self.segment.clear();
+        self.segment.resize(count.div_ceil(64) as usize, u64::MAX);
+        if !count.is_multiple_of(64) {
+            *self.segment.last_mut().expect("nonempty segment") = (1 << (count % 64)) - 1;
+        }
+
+        for &p in &self.sieving {
+            let p2 = p * p;
+            if p2 > end {
+                break;
+            }
+
+            // the first odd multiple of p from max(p², start) on
+            let first = if p2 >= start {
+                Some(p2)
+            } else {
+                let m = start.checked_add((p - start % p) % p);
+                m.and_then(|m| {
+                    if m % 2 == 0 {
+                        m.checked_add(p)
+                    } else {
+                        Some(m)
+                    }
+                })
+            };
+            let Some(mut m) = first else {
+                continue;
+            };
+            while m <= end {
+                let i = (m - start) / 2;
+                self.segment[(i / 64) as usize] &= !(1 << (i % 64));
+                match m.checked_add(2 * p) {
+                    Some(next) => m = next,
+                    None => break,
+                }
+            }
+        }
+
+        self.start = start;
+        self.word = 0;
+        self.bits = self.segment[0];
+        true
+    }
+
+    /// Extends the odd sieving primes up to `bound`.
+    fn grow_sieving(&mut self, bound: u64) {
+        // composite[i] tells whether 2i+1 is composite
+        let mut composite = vec![false; (bound / 2 + 1) as usize];
+        let mut i = 1;
+        while ((2 * i + 1) * (2 * i + 1)) as u64 <= bound {
+            if !composite[i] {
+                let p = 2 * i + 1;
+                for j in (p * p / 2..composite.len()).step_by(p) {
+                    composite[j] = true;
+                }
+            }
+            i += 1;
+        }
+
+        let from = self.sieving_bound + 1;
+        self.sieving.extend(
+            (1..composite.len())
+                .filter(|&i| !composite[i])
+                .map(|i| 2 * i as u64 + 1)
+                .filter(|&p| p >= from && p <= bound),
+        );
+        self.sieving_bound = bound;
+    }
+}
+
+impl Iterator for Primes {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<u64> {
+        if self.two {
+            self.two = false;
+            return Some(2);
+        }
+
+        loop {
+            while self.bits == 0 {
+                self.word += 1;
+                if self.word >= self.segment.len() {
+                    if !self.next_segment() {
+                        return None;
+                    }
+                } else {
+                    self.bits = self.segment[self.word];
+                }
+            }
+
+            let i = 64 * self.word as u64 + self.bits.trailing_zeros() as u64;
+            self.bits &= self.bits - 1;
+            let p = self.start + 2 * i;
+            if !self.confirm || is_prime_u64(p) {
+                return Some(p);
+            }
+        }
+    }
+}
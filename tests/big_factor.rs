@@ -0,0 +1,279 @@
+use std::io::Read;
+use std::str::FromStr;
+
+use math::big::factor::{self, Factorizer};
+use math::big::Int;
+
+mod helper;
+
+lazy_static::lazy_static! {
+  // (n, its factorization as p^e pairs)
+  static ref FACTOR_TESTS: Vec<(&'static str, Vec<(&'static str, u32)>)> = vec![
+    ("1", vec![]),
+    ("-1", vec![("-1", 1)]),
+    ("2", vec![("2", 1)]),
+    ("-12", vec![("-1", 1), ("2", 2), ("3", 1)]),
+    ("360", vec![("2", 3), ("3", 2), ("5", 1)]),
+    ("65521", vec![("65521", 1)]),
+    ("65537", vec![("65537", 1)]),
+    ("4295098369", vec![("65537", 2)]),
+    ("600851475143", vec![("71", 1), ("839", 1), ("1471", 1), ("6857", 1)]),
+    ("18446744073709551617", vec![("274177", 1), ("67280421310721", 1)]),
+    ("147573952589676412927", vec![("193707721", 1), ("761838257287", 1)]),
+    ("1000000016000000063", vec![("1000000007", 1), ("1000000009", 1)]),
+    ("12157665459056928801", vec![("3", 40)]),
+    (
+      "1000000000000000000000000000000000000007",
+      vec![("19", 1), ("347", 1), ("389513", 1), ("157034976251", 1), ("2479696758123328573", 1)],
+    ),
+    ("80000480000720000000", vec![("2", 10), ("5", 7), ("1000003", 2)]),
+    (
+      "-631873747951669116141084886730516277758070510260678832787178913789",
+      vec![("-1", 1), ("3", 1), ("2147483647", 7)],
+    ),
+    (
+      "891281873615184157390995480788601777829",
+      vec![("19777509567454608887", 1), ("45065424975541715267", 1)],
+    ),
+  ];
+}
+
+#[test]
+fn ecm() {
+    let test_vector = vec![
+        // one limb
+        "600851475143",
+        "1000000016000000063",
+        // two limbs
+        "18446744073709551617",
+        "147573952589676412927",
+        // three limbs and more, (2^127-1)·1000000007·193707721
+        "1000000000000000000000000000000000000007",
+        "32957661127074014743958750373000959232571409510501527169",
+    ];
+
+    for s in test_vector {
+        let n = Int::from_str(s).unwrap();
+        let d = factor::ecm(&n, 1000, 100_000, 40).expect(s);
+        assert!(d > Int::new(1) && d < n, "ecm({s}) = {d}");
+        assert_eq!(&n % &d, Int::default(), "ecm({s}) = {d}");
+    }
+
+    // no proper factors
+    for s in [
+        "0",
+        "1",
+        "-1",
+        "2",
+        "3",
+        "65537",
+        "170141183460469231731687303715884105727",
+    ] {
+        let n = Int::from_str(s).unwrap();
+        assert_eq!(factor::ecm(&n, 100, 1000, 2), None, "ecm({s})");
+    }
+
+    // even numbers and perfect powers
+    assert_eq!(factor::ecm(&Int::new(-10), 100, 1000, 1), Some(Int::new(2)));
+    let n = Int::from_str("4611686014132420609").unwrap(); // (2^31-1)^2
+    assert_eq!(factor::ecm(&n, 100, 1000, 1), Some(Int::new(2147483647)));
+}
+
+#[test]
+fn factorize() {
+    for (i, (s, want)) in FACTOR_TESTS.iter().enumerate() {
+        let n = Int::from_str(s).unwrap();
+        let want: Vec<_> = want
+            .iter()
+            .map(|&(p, e)| (Int::from_str(p).unwrap(), e))
+            .collect();
+
+        let got = factor::factorize(&n);
+        assert_eq!(got, want, "#{i} factorize({s})");
+    }
+}
+
+#[test]
+fn factorize_small() {
+    for n in 1..10_000i64 {
+        for n in [n, -n] {
+            let got = factor::factorize(&Int::new(n));
+
+            // against trial division by all integers
+            let (mut m, mut want) = (n.abs(), vec![]);
+            if n < 0 {
+                want.push((Int::new(-1), 1));
+            }
+            let mut d = 2;
+            while d * d <= m {
+                let mut e = 0;
+                while m % d == 0 {
+                    m /= d;
+                    e += 1;
+                }
+                if e > 0 {
+                    want.push((Int::new(d), e));
+                }
+                d += 1;
+            }
+            if m > 1 {
+                want.push((Int::new(m), 1));
+            }
+            assert_eq!(got, want, "factorize({n})");
+        }
+    }
+}
+
+#[test]
+fn factorize_random() {
+    // products of random primes below 2^30, some of them repeated
+    let mut r = helper::rand::Reader::new(20);
+    let mut primes = Vec::new();
+    while primes.len() < 40 {
+        let p = Int::from(rand_u64(&mut r) >> 34 | 1);
+        if p.probably_prime(20) {
+            primes.push(p);
+        }
+    }
+
+    for i in 0..8 {
+        let mut n = Int::new(1);
+        for j in 0..4 {
+            let p = &primes[(i + j * 7) % primes.len()];
+            for _ in 0..=(rand_u64(&mut r) % 3) {
+                n = &n * p;
+            }
+        }
+
+        let got = factor::factorize(&n);
+        check_factorization(&n, &got);
+    }
+}
+
+#[test]
+fn factorizer() {
+    let n = Int::from_str("147573952589676412927").unwrap(); // 2^67 - 1
+    let want = factor::factorize(&n);
+
+    // rho only, restarting many times
+    let got = Factorizer::new()
+        .set_trial_bound(0)
+        .set_pm1_bounds(0, 0)
+        .set_rho_iterations(1)
+        .set_siqs_digits(0)
+        .set_ecm_curves(0)
+        .factor(&n);
+    assert_eq!(got, want);
+
+    // ECM only, starting with too few curves
+    let got = Factorizer::new()
+        .set_trial_bound(0)
+        .set_pm1_bounds(0, 0)
+        .set_rho_iterations(1)
+        .set_siqs_digits(0)
+        .set_ecm_bounds(100, 1000)
+        .set_ecm_curves(1)
+        .factor(&n);
+    assert_eq!(got, want);
+
+    // SIQS only
+    let got = Factorizer::new()
+        .set_trial_bound(0)
+        .set_pm1_bounds(0, 0)
+        .set_rho_iterations(1)
+        .set_ecm_curves(0)
+        .factor(&n);
+    assert_eq!(got, want);
+
+    // p-1 finds p = 32525382325199096891 at once, since
+    // p-1 = 2·5·41·43²·47·73·79·97·101·107·151
+    let n =
+        Int::from_str("32525382325199096891000000000000000000000000292728440926791872019").unwrap();
+    let got = Factorizer::new()
+        .set_trial_bound(100)
+        .set_pm1_bounds(10_000, 0)
+        .set_rho_iterations(1)
+        .factor(&n);
+    check_factorization(&n, &got);
+    let p = Int::from_str("32525382325199096891").unwrap();
+    assert!(got.iter().any(|(q, _)| *q == p));
+
+    // even numbers without trial division
+    let got = Factorizer::new().set_trial_bound(0).factor(&Int::new(-96));
+    let want = vec![(Int::new(-1), 1), (Int::new(2), 5), (Int::new(3), 1)];
+    assert_eq!(got, want);
+}
+
+#[test]
+#[should_panic(expected = "cannot factor 0")]
+fn factorize_zero() {
+    factor::factorize(&Int::default());
+}
+
+#[test]
+fn siqs() {
+    let test_vector = vec![
+        "1000000016000000063",
+        "1062828501495832249699",
+        "12688272555974896325528903",
+        "112317932202943735387945909397",
+        "3995315338725816901609421580209381",
+        "891281873615184157390995480788601777829",
+        // 101·(2^89-1), where 101 is in the factor base
+        "62515971983911703882405773211",
+    ];
+
+    for s in test_vector {
+        let n = Int::from_str(s).unwrap();
+        let d = factor::siqs(&n).expect(s);
+        assert!(d > Int::new(1) && d < n, "siqs({s}) = {d}");
+        assert_eq!(&n % &d, Int::default(), "siqs({s}) = {d}");
+    }
+
+    // no proper factors
+    for s in [
+        "0",
+        "1",
+        "-1",
+        "2",
+        "3",
+        "65537",
+        "170141183460469231731687303715884105727",
+    ] {
+        let n = Int::from_str(s).unwrap();
+        assert_eq!(factor::siqs(&n), None, "siqs({s})");
+    }
+
+    // even numbers and perfect powers
+    assert_eq!(factor::siqs(&Int::new(-10)), Some(Int::new(2)));
+    let n = Int::from_str("4611686014132420609").unwrap(); // (2^31-1)^2
+    assert_eq!(factor::siqs(&n), Some(Int::new(2147483647)));
+}
+
+/// Checks that `factors` is a factorization of `n` into primes in increasing order.
+fn check_factorization(n: &Int, factors: &[(Int, u32)]) {
+    let mut product = Int::new(1);
+    let mut last = Int::default();
+    for (i, (p, e)) in factors.iter().enumerate() {
+        assert!(*e > 0, "{n}: {p}^{e}");
+        if *p == Int::new(-1) {
+            assert_eq!((i, *e), (0, 1), "{n}: -1 must lead with exponent 1");
+        } else {
+            assert!(p.probably_prime(20), "{n}: {p} isn't prime");
+            assert!(*p > last, "{n}: {p} out of order");
+            last = p.clone();
+        }
+
+        for _ in 0..*e {
+            product = &product * p;
+        }
+    }
+
+    assert_eq!(product, *n, "{n}: {factors:?}");
+}
+
+fn rand_u64<R: Read>(r: &mut R) -> u64 {
+    let mut b = [0u8; 8];
+    r.read_exact(&mut b).unwrap();
+    u64::from_be_bytes(b)
+}
@@ -1,4 +1,7 @@
-use math::big::{self, Int};
+use std::cmp::Ordering;
+use std::collections::{BTreeSet, HashSet};
+
+use math::big::{self, Accuracy, Int, Word};
 
 use strconv::NumErrorCause;
 
@@ -6,6 +9,9 @@ mod helper;
 
 use helper::is_big_int_normalized as is_normalized;
 
+/// Bits per [Word].
+const W: usize = Word::BITS as usize;
+
 lazy_static::lazy_static! {
   static ref BITWISE_TESTS: Vec<BitwiseTest> = vec![
     BitwiseTest::new("0x00", "0x00", "0x00", "0x00", "0x00", "0x00"),
@@ -59,6 +65,78 @@ lazy_static::lazy_static! {
     "32957394867987420967976567076075976570670947609750670956097509670576075067076027578341538",
   ];
 
+  static ref COMPOSITES: Vec<&'static str> = vec![
+    "0",
+    "1",
+    "21284175091214687912771199898307297748211672914763848041968395774954376176754",
+    "6084766654921918907427900243509372380954290099172559290432744450051395395951",
+    "84594350493221918389213352992032324280367711247940675652888030554255915464401",
+    "82793403787388584738507275144194252681",
+
+    // Arnault, "Rabin-Miller Primality Test: Composite Numbers Which Pass It",
+    // Mathematics of Computation, 64(209) (January 1995), pp. 335-361.
+    "1195068768795265792518361315725116351898245581", // strong pseudoprime to prime bases 2 through 29
+    // strong pseudoprime to all prime bases up to 200
+    "
+     80383745745363949125707961434194210813883768828755814583748891752229
+      74273765333652186502336163960045457915042023603208766569966760987284
+       0439654082329287387918508691668573282677617710293896977394701670823
+        0428687109997439976544144845341155872450633409279022275296229414984
+         2306881685404326457534018329786111298960644845216191652872597534901",
+
+    // Extra-strong Lucas pseudoprimes. https://oeis.org/A217719
+    "989",
+    "3239",
+    "5777",
+    "10877",
+    "27971",
+    "29681",
+    "30739",
+    "31631",
+    "39059",
+    "72389",
+    "73919",
+    "75077",
+    "100127",
+    "113573",
+    "125249",
+    "137549",
+    "137801",
+    "153931",
+    "155819",
+    "161027",
+    "162133",
+    "189419",
+    "218321",
+    "231703",
+    "249331",
+    "370229",
+    "429479",
+    "430127",
+    "459191",
+    "473891",
+    "480689",
+    "600059",
+    "621781",
+    "632249",
+    "635627",
+
+    "3673744903",
+    "3281593591",
+    "2385076987",
+    "2738053141",
+    "2009621503",
+    "1502682721",
+    "255866131",
+    "117987841",
+    "587861",
+
+    "6368689",
+    "8725753",
+    "80579735209",
+    "105919633",
+  ];
+
   static ref LSH_TESTS: Vec<IntShiftTest> = vec![
     IntShiftTest::new("0", 0, "0"),
     IntShiftTest::new("0", 1, "0"),
@@ -235,7 +313,7 @@ fn abs_z() {
         z.abs(&a.z);
         let mut e = Int::default();
         e.set(&a.z);
-        if e.cmp(&zero) < 0 {
+        if e.cmp_i32(&zero) < 0 {
             let v = e.clone();
             e.sub(&zero, &v);
         }
@@ -282,6 +360,43 @@ fn binomial() {
     }
 }
 
+#[test]
+fn binomial_pascal() {
+    let mut row = vec![Int::new(1)];
+    let mut z = Int::default();
+    for n in 0..300i64 {
+        for k in -2..=n + 2 {
+            let want = match k {
+                k if k < 0 => Int::new(1),
+                k if k > n => Int::default(),
+                k => row[k as usize].clone(),
+            };
+            assert_eq!(*z.binomial(n, k), want, "binomial({n}, {k})");
+        }
+
+        let mut next = vec![Int::new(1); row.len() + 1];
+        for k in 1..row.len() {
+            next[k].add(&row[k - 1], &row[k]);
+        }
+        row = next;
+    }
+
+    // C(n, k) = n·(n-1)···(n-k+1) / k!
+    for (n, k) in [
+        (1i64 << 40, 3i64),
+        (1 << 62, 40),
+        (100_000, 1000),
+        (20_000, 10_000),
+    ] {
+        let (mut num, mut den, mut want) = (Int::default(), Int::default(), Int::default());
+        num.mul_range(n - k + 1, n);
+        den.factorial(k as u64);
+        want.quo(&num, &den);
+        assert_eq!(*z.binomial(n, k), want, "binomial({n}, {k})");
+        assert_eq!(*z.binomial(n, n - k), want, "binomial({n}, {})", n - k);
+    }
+}
+
 #[test]
 fn bit_len() {
     struct Case {
@@ -309,7 +424,7 @@ fn bit_len() {
     for (i, c) in test_vector.iter().enumerate() {
         let mut x = Int::default();
         assert!(
-            x.set_string(c.input, 0).is_some(),
+            x.set_string(c.input, 0).is_ok(),
             "#{i} set_string({}, 0)",
             c.input
         );
@@ -360,7 +475,7 @@ fn bit_set() {
 #[test]
 fn bits() {
     let test_vector = vec![
-        vec![0u32],
+        vec![0 as Word],
         vec![1],
         vec![0, 1, 2, 3, 4],
         vec![4, 3, 2, 1, 0],
@@ -375,10 +490,10 @@ fn bits() {
         assert!(got.sign() >= 0, "set_bits({c:?}): get negative result");
 
         let want = norm(c.as_slice());
-        let got: Vec<u32> = got.bits().collect();
+        let got: Vec<Word> = got.bits().collect();
         assert_eq!(got, want, "set_bits({c:?})");
 
-        let bits: Vec<u32> = z.bits().collect();
+        let bits: Vec<Word> = z.bits().collect();
         assert_eq!(
             bits,
             want,
@@ -390,6 +505,46 @@ fn bits() {
     }
 }
 
+#[test]
+fn bits_kernel() {
+    // a custom kernel: increment of the magnitude with carry propagation
+    fn inc(x: &Int) -> Vec<Word> {
+        let mut carry = true;
+        let mut abs: Vec<Word> = x
+            .bits()
+            .map(|w| {
+                let (v, c) = w.overflowing_add(carry as Word);
+                carry = c;
+                v
+            })
+            .collect();
+        if carry {
+            abs.push(1);
+        }
+        abs
+    }
+
+    let mut z = Int::default();
+    z.set_bits(&[Word::MAX, Word::MAX]);
+    assert_eq!(z.bits().len(), 2);
+    assert_eq!(z.bits().rev().collect::<Vec<_>>(), vec![Word::MAX; 2]);
+
+    let abs = inc(&z);
+    assert_eq!(abs, vec![0, 0, 1]);
+    z.set_bits(&abs);
+
+    let mut want = Int::default();
+    want.lsh(&Int::new(1), 2 * W);
+    assert_eq!(z, want);
+
+    // leading zero words are dropped and a zero result is non-negative
+    z.set_bits(&[0, 0, 0]);
+    assert_eq!(z, 0i64);
+    assert_eq!(z.sign(), 0);
+    assert_eq!(z.bits().next(), None);
+    assert!(is_normalized(&z), "{z} is not normalized");
+}
+
 #[test]
 fn bitwise() {
     let mut x = Int::default();
@@ -429,7 +584,7 @@ fn cmp_abs() {
 
         if let Some(v) = prev {
             assert!(
-                v.cmp(&x) < 0,
+                v.cmp_i32(&x) < 0,
                 "CMP_ABS_TESTS entries not sorted in ascending order"
             );
         }
@@ -459,17 +614,115 @@ fn cmp_abs() {
                 }
 
                 let got = a.cmp_abs(&b);
-                let want = if i > j {
-                    1
-                } else if i < j {
-                    -1
-                } else {
-                    0
-                };
-                assert_eq!(got, want, "cmp_abs |{a}|, |{b}|");
+                let want = i.cmp(&j);
+                assert_eq!(got, want as i32, "cmp_abs |{a}|, |{b}|");
+
+                let got = a.cmp_magnitude(&b);
+                assert_eq!(got, want, "cmp_magnitude |{a}|, |{b}|");
+            }
+        }
+    }
+}
+
+#[test]
+fn crt() {
+    struct Case {
+        residues: &'static [i64],
+        moduli: &'static [i64],
+        want: Option<(i64, i64)>,
+    }
+
+    fn new_case(
+        residues: &'static [i64],
+        moduli: &'static [i64],
+        want: Option<(i64, i64)>,
+    ) -> Case {
+        Case {
+            residues,
+            moduli,
+            want,
+        }
+    }
+
+    let test_vector = vec![
+        new_case(&[], &[], Some((0, 1))),
+        new_case(&[7], &[1], Some((0, 1))),
+        new_case(&[-1], &[5], Some((4, 5))),
+        new_case(&[12], &[5], Some((2, 5))),
+        new_case(&[2, 3, 2], &[3, 5, 7], Some((23, 105))),
+        new_case(&[3, 5], &[4, 6], Some((11, 12))),
+        new_case(&[1, 2], &[4, 6], None),
+        new_case(&[10, 4], &[6, 9], Some((4, 18))),
+        new_case(&[1, 1], &[6, 6], Some((1, 6))),
+        new_case(&[1, 2], &[6, 6], None),
+        new_case(&[0, 0, 0, 0], &[2, 4, 8, 16], Some((0, 16))),
+        new_case(&[1, 3, 7, 9], &[2, 4, 8, 16], None),
+        new_case(&[1, 3, 7, 15], &[2, 4, 8, 16], Some((15, 16))),
+    ];
+
+    for (i, c) in test_vector.iter().enumerate() {
+        let r: Vec<_> = c.residues.iter().map(|&v| Int::new(v)).collect();
+        let m: Vec<_> = c.moduli.iter().map(|&v| Int::new(v)).collect();
+        let want = c.want.map(|(x, l)| (Int::new(x), Int::new(l)));
+        assert_eq!(
+            big::crt(&r, &m),
+            want,
+            "#{i} crt({:?}, {:?})",
+            c.residues,
+            c.moduli
+        );
+    }
+
+    // all pairs of congruences with small moduli against a search
+    for m1 in 1..=16i64 {
+        for m2 in 1..=16i64 {
+            let l = (1..).map(|k| k * m1).find(|v| v % m2 == 0).unwrap();
+            for r1 in 0..m1 {
+                for r2 in 0..m2 {
+                    let want = (0..l).find(|x| x % m1 == r1 && x % m2 == r2);
+                    let got =
+                        big::crt(&[Int::new(r1), Int::new(r2)], &[Int::new(m1), Int::new(m2)]);
+                    let want = want.map(|x| (Int::new(x), Int::new(l)));
+                    assert_eq!(got, want, "crt([{r1}, {r2}], [{m1}, {m2}])");
+                }
             }
         }
     }
+
+    // reconstruct a big value from its residues modulo many random, mostly
+    // non-coprime moduli
+    let mut x = Int::default();
+    x.set_bytes(&rand_bytes(1000));
+    let moduli: Vec<_> = (0..600)
+        .map(|_| Int::from(randn(2, u64::MAX) as u64))
+        .collect();
+    let residues: Vec<_> = moduli.iter().map(|m| &x % m).collect();
+
+    let mut lcm = Int::new(1);
+    for m in &moduli {
+        let mut g = Int::default();
+        g.gcd(None, None, &lcm, m);
+        lcm = &(&lcm / &g) * m;
+    }
+
+    let (y, l) = big::crt(&residues, &moduli).expect("consistent congruences");
+    assert_eq!(l, lcm);
+    assert_eq!(y, &x % &lcm);
+
+    // and spoil one of them
+    let mut residues = residues;
+    let i = moduli
+        .iter()
+        .position(|m| m.bit(0) == 0)
+        .expect("some even modulus");
+    residues[i] = &residues[i] + 1u64;
+    assert_eq!(big::crt(&residues, &moduli), None);
+}
+
+#[test]
+#[should_panic(expected = "moduli must be positive")]
+fn crt_zero_modulus() {
+    big::crt(&[Int::new(1), Int::new(1)], &[Int::new(3), Int::default()]);
 }
 
 #[test]
@@ -551,6 +804,135 @@ fn division_signs() {
     }
 }
 
+#[test]
+fn dlog() {
+    // against exhaustive search over the units, none of which generates all of
+    // (Z/15Z)*
+    for (p, order) in [(2i64, 1i64), (11, 10), (15, 4), (101, 100)] {
+        for g in (1..p).filter(|&g| (0..order).fold(1, |y, _| y * g % p) == 1) {
+            let mut want = vec![None; p as usize];
+            let mut y = 1;
+            for x in 0..p {
+                if want[y as usize].is_some() {
+                    break;
+                }
+                want[y as usize] = Some(Int::new(x));
+                y = y * g % p;
+            }
+
+            for h in 0..p {
+                let got = big::dlog(&Int::new(g), &Int::new(h), &Int::new(p), &Int::new(order));
+                assert_eq!(got, want[h as usize], "dlog({g}, {h}, {p}, {order})");
+            }
+        }
+    }
+
+    // 2^127-1 has a 37-bit prime factor, which falls to the rho method
+    let p = (Int::new(1) << 127usize) - 1i64;
+    let order = &p - 1i64;
+    let g = Int::new(3);
+    let x = int_from_decimal_str("123456789012345678901234567890123");
+    let mut h = Int::default();
+    h.exp(&g, &x, Some(&p));
+    assert_eq!(big::dlog(&g, &h, &p, &order), Some(x));
+    // 3 generates the cubes only, and 5 isn't one
+    assert_eq!(big::dlog(&g, &(&h * 5i64), &p, &order), None);
+
+    // q = 2^33+17 divides r0-1 and r1-1 for p = r0·r1, and g and h generate
+    // different subgroups of order q
+    let p = int_from_decimal_str("70245201711257551648471");
+    let q = int_from_decimal_str("8589934609");
+    let g = int_from_decimal_str("69879341287766602563942");
+    let h = int_from_decimal_str("23415067189302907630962");
+    assert_eq!(big::dlog(&g, &h, &p, &q), None);
+    let x = Int::new(5_000_000_000);
+    let mut h = Int::default();
+    h.exp(&g, &x, Some(&p));
+    assert_eq!(big::dlog(&g, &h, &p, &q), Some(x));
+}
+
+#[test]
+fn dlog_factored() {
+    // p-1 = 2·q·c for a 36-bit prime q and a 200-bit semiprime c, which is left
+    // unfactored since g generates the subgroup of order q
+    let p = int_from_decimal_str(
+        "63273164823552111642543975622576693643563251115828058057857208517810107",
+    );
+    let q = int_from_decimal_str("49424060149");
+    let g = int_from_decimal_str(
+        "40298174473670724672003440270420084935092600918057038243569942384867908",
+    );
+    let x = Int::new(13742245381);
+    let mut h = Int::default();
+    h.exp(&g, &x, Some(&p));
+    assert_eq!(big::dlog_factored(&g, &h, &p, &[(q.clone(), 1)]), Some(x));
+    assert_eq!(big::dlog_factored(&g, &Int::new(2), &p, &[(q, 1)]), None);
+
+    // the factorization of 2^127-2
+    let p = (Int::new(1) << 127usize) - 1i64;
+    let order: Vec<_> = [
+        ("2", 1),
+        ("3", 3),
+        ("7", 2),
+        ("19", 1),
+        ("43", 1),
+        ("73", 1),
+        ("127", 1),
+        ("337", 1),
+        ("5419", 1),
+        ("92737", 1),
+        ("649657", 1),
+        ("77158673929", 1),
+    ]
+    .iter()
+    .map(|&(q, e)| (int_from_decimal_str(q), e))
+    .collect();
+    let (g, x) = (Int::new(3), Int::new(1234567));
+    let mut h = Int::default();
+    h.exp(&g, &x, Some(&p));
+    assert_eq!(big::dlog_factored(&g, &h, &p, &order), Some(x));
+}
+
+#[test]
+#[should_panic(expected = "order must be a multiple of the order of g")]
+fn dlog_bad_order() {
+    big::dlog(&Int::new(2), &Int::new(4), &Int::new(11), &Int::new(5));
+}
+
+#[test]
+#[should_panic(expected = "g must be a unit modulo p")]
+fn dlog_non_unit() {
+    big::dlog(&Int::new(6), &Int::new(6), &Int::new(15), &Int::new(4));
+}
+
+#[test]
+fn double_factorial() {
+    let test_vector = vec![
+        (0, "1"),
+        (1, "1"),
+        (2, "2"),
+        (3, "3"),
+        (4, "8"),
+        (9, "945"),
+        (10, "3840"),
+        (25, "7905853580625"),
+        (26, "51011754393600"),
+    ];
+
+    let mut z = Int::default();
+    for (n, want) in test_vector {
+        assert_eq!(z.double_factorial(n).to_string(), want, "{n}!!");
+    }
+
+    // n!! = n·(n-2)!!
+    let mut want = [Int::new(1), Int::new(1)];
+    for n in 2..1000u64 {
+        let w = &mut want[n as usize % 2];
+        *w = &*w * n;
+        assert_eq!(*z.double_factorial(n), *w, "{n}!!");
+    }
+}
+
 #[test]
 fn exp() {
     struct Case {
@@ -645,8 +1027,8 @@ new_case(
 
         let mut out = Int::default();
         match out.set_string(s, 0) {
-            Some(_) => Ok(Some(out)),
-            None => Err(None::<Int>),
+            Ok(_) => Ok(Some(out)),
+            Err(_) => Err(None::<Int>),
         }
     };
 
@@ -671,7 +1053,7 @@ new_case(
 
         match &zz {
             None if out.is_none() => {}
-            Some(v) if out.is_some() && (v.cmp(out.as_ref().unwrap()) == 0) => {}
+            Some(v) if out.is_some() && (v.cmp_i32(out.as_ref().unwrap()) == 0) => {}
             _ => panic!("#{i}: got {zz:?}, want {out:?}"),
         }
 
@@ -684,6 +1066,134 @@ new_case(
     }
 }
 
+#[test]
+fn factorial() {
+    let test_vector = vec![
+        (0, "1"),
+        (1, "1"),
+        (2, "2"),
+        (3, "6"),
+        (10, "3628800"),
+        (20, "2432902008176640000"),
+        (25, "15511210043330985984000000"),
+    ];
+
+    let mut z = Int::default();
+    for (n, want) in test_vector {
+        assert_eq!(z.factorial(n).to_string(), want, "{n}!");
+    }
+
+    let mut want = Int::new(1);
+    for n in 1..1000u64 {
+        want = &want * n;
+        assert_eq!(*z.factorial(n), want, "{n}!");
+    }
+
+    let mut prod = Int::default();
+    for n in [4095u64, 4096, 30_000, 65_537] {
+        prod.mul_range(1, n as i64);
+        assert_eq!(*z.factorial(n), prod, "{n}!");
+    }
+
+    // 10^5! has 456574 digits, 24999 of them trailing zeros
+    let s = z.factorial(100_000).to_string();
+    assert_eq!(s.len(), 456_574);
+    assert_eq!(s.len() - s.trim_end_matches('0').len(), 24_999);
+}
+
+#[test]
+fn fibonacci() {
+    let (mut f, mut l) = (
+        vec![Int::new(0), Int::new(1)],
+        vec![Int::new(2), Int::new(1)],
+    );
+    for n in 2..600 {
+        f.push(&f[n - 1] + &f[n - 2]);
+        l.push(&l[n - 1] + &l[n - 2]);
+    }
+
+    let mut z = Int::default();
+    for n in 0..600 {
+        assert_eq!(*z.fibonacci(n as u64), f[n], "F({n})");
+        assert_eq!(*z.lucas(n as u64), l[n], "L({n})");
+        let (mut zf, mut zl) = (Int::default(), Int::default());
+        let (gf, gl) = zf.fib_pair(&mut zl, n as u64);
+        assert_eq!((&*gf, &*gl), (&f[n], &l[n]), "fib_pair({n})");
+    }
+
+    let test_vector = vec![
+        (10, "55", "123"),
+        (64, "10610209857723", "23725150497407"),
+        (93, "12200160415121876738", "27280388024614569596"),
+        (
+            200,
+            "280571172992510140037611932413038677189525",
+            "627376215338105766356982006981782561278127",
+        ),
+    ];
+
+    for (n, f, l) in test_vector {
+        assert_eq!(z.fibonacci(n).to_string(), f, "F({n})");
+        assert_eq!(z.lucas(n).to_string(), l, "L({n})");
+    }
+
+    // L(n)² - 5 F(n)² = 4(-1)^n
+    for n in [100_000u64, 100_001] {
+        let (mut f, mut l) = (Int::default(), Int::default());
+        f.fib_pair(&mut l, n);
+        let d = &(&l * &l) - &(&(&f * &f) * 5u64);
+        assert_eq!(d, Int::new(if n % 2 == 0 { 4 } else { -4 }), "n = {n}");
+    }
+    assert_eq!(z.fibonacci(100_000).to_string().len(), 20_899);
+}
+
+#[test]
+fn fibonacci_mod() {
+    let mut z = Int::default();
+    let (mut f, mut l) = (Int::default(), Int::default());
+    for m in [1i64, 2, 3, 10, 64, 97, 1000, 1 << 40, 1_000_000_007] {
+        let m = Int::new(m);
+        for n in 0..300i64 {
+            f.fibonacci(n as u64);
+            l.lucas(n as u64);
+
+            let want_f = &f % &m;
+            let want_l = &l % &m;
+            assert_eq!(*z.fibonacci_mod(&Int::new(n), &m), want_f, "F({n}) mod {m}");
+            assert_eq!(*z.lucas_mod(&Int::new(n), &m), want_l, "L({n}) mod {m}");
+
+            // F(-n) = (-1)^(n+1) F(n), L(-n) = (-1)^n L(n)
+            let (sf, sl) = if n % 2 == 0 { (-1i64, 1i64) } else { (1, -1) };
+            let mut want = Int::default();
+            want.r#mod(&(&f * &Int::new(sf)), &m);
+            assert_eq!(
+                *z.fibonacci_mod(&Int::new(-n), &m),
+                want,
+                "F({}) mod {m}",
+                -n
+            );
+            want.r#mod(&(&l * &Int::new(sl)), &m);
+            assert_eq!(*z.lucas_mod(&Int::new(-n), &m), want, "L({}) mod {m}", -n);
+        }
+    }
+
+    // the Pisano period modulo 10^k is 15·10^(k-1) for k >= 3
+    let m = Int::new(1_000_000);
+    let mut n = Int::default();
+    n.exp(&Int::new(10), &Int::new(100), None);
+    n = &n * 1_500_000u64;
+    assert_eq!(*z.fibonacci_mod(&n, &m), Int::new(0));
+    assert_eq!(*z.lucas_mod(&n, &m), Int::new(2));
+    assert_eq!(*z.fibonacci_mod(&(&n + 1u64), &m), Int::new(1));
+    assert_eq!(*z.fibonacci_mod(&(&n + 30u64), &m), Int::new(832_040));
+}
+
+#[test]
+#[should_panic(expected = "modulus must be positive")]
+fn fibonacci_mod_zero() {
+    Int::default().fibonacci_mod(&Int::new(1), &Int::default());
+}
+
 #[test]
 fn fill_bytes() {
     fn check_result(ctx: &str, buf: &[u8], want: &Int) {
@@ -733,6 +1243,118 @@ fn fill_bytes() {
     }
 }
 
+#[test]
+fn float32() {
+    let test_vector = vec![
+        ("0", 0.0, Accuracy::Exact),
+        ("1", 1.0, Accuracy::Exact),
+        ("-1", -1.0, Accuracy::Exact),
+        ("16777216", 16777216.0, Accuracy::Exact), // 1<<24
+        ("16777217", 16777216.0, Accuracy::Below), // tie, rounds to even
+        ("16777219", 16777220.0, Accuracy::Above), // tie, rounds to even
+        ("-16777217", -16777216.0, Accuracy::Above),
+        ("16777218", 16777218.0, Accuracy::Exact),
+        ("0x100000100000001", 72057602627862528.0, Accuracy::Above), // above tie
+        (
+            "0xffffff00000000000000000000000000",
+            f32::MAX,
+            Accuracy::Exact,
+        ),
+        (
+            "0xffffff7fffffffffffffffffffffffff",
+            f32::MAX,
+            Accuracy::Below,
+        ),
+        (
+            "0xffffff80000000000000000000000000",
+            f32::INFINITY,
+            Accuracy::Above,
+        ),
+        (
+            "-0xffffff80000000000000000000000000",
+            f32::NEG_INFINITY,
+            Accuracy::Below,
+        ),
+    ];
+
+    for (i, &(s, want, acc)) in test_vector.iter().enumerate() {
+        let x = int_from_str(s, None);
+        let (got, got_acc) = x.float32();
+        assert_eq!(got, want, "#{i} float32({s})");
+        assert_eq!(got_acc, acc, "#{i} float32({s}) accuracy");
+    }
+}
+
+#[test]
+fn float64() {
+    let test_vector = vec![
+        ("0", 0.0, Accuracy::Exact),
+        ("1", 1.0, Accuracy::Exact),
+        ("-1", -1.0, Accuracy::Exact),
+        ("9007199254740992", 9007199254740992.0, Accuracy::Exact), // 1<<53
+        ("9007199254740993", 9007199254740992.0, Accuracy::Below), // tie, rounds to even
+        ("9007199254740995", 9007199254740996.0, Accuracy::Above), // tie, rounds to even
+        ("-9007199254740993", -9007199254740992.0, Accuracy::Above),
+        ("-9007199254740995", -9007199254740996.0, Accuracy::Below),
+        ("18446744073709551615", 18446744073709551616.0, Accuracy::Above), // 1<<64 - 1
+        ("0x20000000000001000000000000000001", 4.253529586511732e37, Accuracy::Above), // above tie
+        (
+            "0xfffffffffffff800000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+            f64::MAX,
+            Accuracy::Exact,
+        ),
+        (
+            "0xfffffffffffffbffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+            f64::MAX,
+            Accuracy::Below,
+        ),
+        (
+            "0xfffffffffffffc00000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+            f64::INFINITY,
+            Accuracy::Above,
+        ),
+        (
+            "-0x10000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000",
+            f64::NEG_INFINITY,
+            Accuracy::Below,
+        ),
+    ];
+
+    for (i, &(s, want, acc)) in test_vector.iter().enumerate() {
+        let x = int_from_str(s, None);
+        let (got, got_acc) = x.float64();
+        assert_eq!(got, want, "#{i} float64({s})");
+        assert_eq!(got_acc, acc, "#{i} float64({s}) accuracy");
+    }
+
+    // round-trip powers of two, and their neighbours
+    let mut x = Int::default();
+    for n in 0..1024 {
+        x.lsh(&Int::new(1), n);
+        assert_eq!(
+            x.float64(),
+            (2f64.powi(n as i32), Accuracy::Exact),
+            "1<<{n}"
+        );
+
+        x.sub(&x.clone(), &Int::new(1));
+        let (got, acc) = x.float64();
+        if n <= 53 {
+            assert_eq!(
+                (got, acc),
+                (x.int64() as f64, Accuracy::Exact),
+                "1<<{n} - 1"
+            );
+        } else {
+            assert_eq!(
+                (got, acc),
+                (2f64.powi(n as i32), Accuracy::Above),
+                "1<<{n} - 1"
+            );
+        }
+    }
+}
+
 #[test]
 fn gcd() {
     struct Case {
@@ -801,6 +1423,94 @@ fn gcd() {
     }
 }
 
+#[test]
+fn ilog() {
+    for base in 2i64..=40 {
+        let b = Int::new(base);
+
+        // base^k - 1, base^k and base^k + 1 around every power up to ~600 bits
+        let mut p = Int::new(1);
+        let mut k = 0;
+        while p.bit_len() < 600 {
+            assert_eq!(p.ilog(&b), k, "ilog_{base}({base}^{k})");
+            if base > 2 || k > 0 {
+                assert_eq!((&p + 1u64).ilog(&b), k, "ilog_{base}({base}^{k} + 1)");
+            }
+            if k > 0 {
+                assert_eq!((&p - 1u64).ilog(&b), k - 1, "ilog_{base}({base}^{k} - 1)");
+            }
+
+            p = &p * &b;
+            k += 1;
+        }
+    }
+
+    let test_vector = vec![
+        ("1", 0, 0),
+        ("9", 3, 0),
+        ("10", 3, 1),
+        ("99", 6, 1),
+        ("100", 6, 2),
+        ("0xffffffffffffffff", 63, 19),
+        ("0x10000000000000000", 64, 19),
+        ("100000000000000000000", 66, 20),
+    ];
+
+    for (s, log2, log10) in test_vector {
+        let x = int_from_str(s, Some(0));
+        assert_eq!(x.ilog2(), log2, "ilog2({s})");
+        assert_eq!(x.ilog10(), log10, "ilog10({s})");
+        assert_eq!(x.ilog(&Int::new(2)), log2, "ilog({s}, 2)");
+    }
+
+    // a thousand digits, with a huge base
+    let mut x = Int::default();
+    x.exp(&Int::new(10), &Int::new(1000), None);
+    assert_eq!(x.ilog10(), 1000);
+    assert_eq!((&x - 1u64).ilog10(), 999);
+    let b = int_from_str(&format!("1{}", "0".repeat(333)), Some(10));
+    assert_eq!(x.ilog(&b), 3);
+    assert_eq!(b.ilog(&x), 0);
+}
+
+#[test]
+#[should_panic(expected = "argument of integer logarithm must be positive")]
+fn ilog_zero() {
+    Int::default().ilog10();
+}
+
+#[test]
+#[should_panic(expected = "base of integer logarithm must be at least 2")]
+fn ilog_bad_base() {
+    Int::new(10).ilog(&Int::new(1));
+}
+
+#[test]
+fn int32() {
+    let test_vector = vec![
+        // int32
+        ("0", Some(0)),
+        ("1", Some(1)),
+        ("-1", Some(-1)),
+        ("2147483647", Some(i32::MAX)),
+        ("-2147483648", Some(i32::MIN)),
+        // not int32
+        ("2147483648", None),
+        ("-2147483649", None),
+        ("4294967296", None),
+        ("38579843757496759476987459679745", None),
+    ];
+
+    for (s, want) in test_vector {
+        let x = int_from_str(s, None);
+        assert_eq!(x.is_int32(), want.is_some(), "is_int32({s})");
+        assert_eq!(x.checked_int32(), want, "checked_int32({s})");
+        if let Some(v) = want {
+            assert_eq!(x.int32(), v, "int32({s})");
+        }
+    }
+}
+
 #[test]
 fn int64() {
     let test_vector = vec![
@@ -830,7 +1540,8 @@ fn int64() {
             Err(err) => {
                 match err.err {
                     NumErrorCause::OutOfRangeSigned { .. } => {
-                        assert!(!x.is_int64(), "is_int64({x}) succeeded unexpectedly")
+                        assert!(!x.is_int64(), "is_int64({x}) succeeded unexpectedly");
+                        assert_eq!(x.checked_int64(), None, "checked_int64({x})");
                     }
                     _ => panic!("parse_int({s}) failed"),
                 }
@@ -842,6 +1553,124 @@ fn int64() {
         assert!(x.is_int64(), "is_int64({x}) failed unexpectedly");
 
         assert_eq!(x.int64(), want, "int64({s})");
+        assert_eq!(x.checked_int64(), Some(want), "checked_int64({s})");
+    }
+}
+
+#[test]
+fn int64_wraparound() {
+    struct Case {
+        input: &'static str,
+        int64: i64,
+        uint64: u64,
+        wrapping_uint64: u64,
+        int32: i32,
+        uint32: u32,
+        wrapping_uint32: u32,
+    }
+
+    let new_case = |input, int64, uint64, wrapping_uint64, int32, uint32, wrapping_uint32| Case {
+        input,
+        int64,
+        uint64,
+        wrapping_uint64,
+        int32,
+        uint32,
+        wrapping_uint32,
+    };
+
+    let test_vector = vec![
+        new_case("0", 0, 0, 0, 0, 0, 0),
+        new_case("1", 1, 1, 1, 1, 1, 1),
+        new_case("-1", -1, 1, 18446744073709551615, -1, 1, 4294967295),
+        new_case("4294967296", 4294967296, 4294967296, 4294967296, 0, 0, 0),
+        new_case(
+            "-4294967297",
+            -4294967297,
+            4294967297,
+            18446744069414584319,
+            -1,
+            1,
+            4294967295,
+        ),
+        new_case(
+            "0x8000000000000000",
+            -9223372036854775808,
+            9223372036854775808,
+            9223372036854775808,
+            0,
+            0,
+            0,
+        ),
+        new_case(
+            "-0x8000000000000001",
+            9223372036854775807,
+            9223372036854775809,
+            9223372036854775807,
+            -1,
+            1,
+            4294967295,
+        ),
+        new_case(
+            "0x1ffffffffffffffff",
+            -1,
+            18446744073709551615,
+            18446744073709551615,
+            -1,
+            4294967295,
+            4294967295,
+        ),
+        new_case(
+            "38579843757496759476987459679745",
+            8589832899396515329,
+            8589832899396515329,
+            8589832899396515329,
+            -662817279,
+            3632150017,
+            3632150017,
+        ),
+        new_case(
+            "-38579843757496759476987459679745",
+            -8589832899396515329,
+            8589832899396515329,
+            9856911174313036287,
+            662817279,
+            3632150017,
+            662817279,
+        ),
+    ];
+
+    for (i, c) in test_vector.iter().enumerate() {
+        let x = int_from_str(c.input, None);
+
+        assert_eq!(x.int64(), c.int64, "#{i} int64({})", c.input);
+        assert_eq!(
+            x.wrapping_int64(),
+            c.int64,
+            "#{i} wrapping_int64({})",
+            c.input
+        );
+        assert_eq!(x.uint64(), c.uint64, "#{i} uint64({})", c.input);
+        assert_eq!(
+            x.wrapping_uint64(),
+            c.wrapping_uint64,
+            "#{i} wrapping_uint64({})",
+            c.input
+        );
+        assert_eq!(x.int32(), c.int32, "#{i} int32({})", c.input);
+        assert_eq!(
+            x.wrapping_int32(),
+            c.int32,
+            "#{i} wrapping_int32({})",
+            c.input
+        );
+        assert_eq!(x.uint32(), c.uint32, "#{i} uint32({})", c.input);
+        assert_eq!(
+            x.wrapping_uint32(),
+            c.wrapping_uint32,
+            "#{i} wrapping_uint32({})",
+            c.input
+        );
     }
 }
 
@@ -851,9 +1680,158 @@ fn int_cmp_self() {
         let mut x = Int::default();
         x.set_string(s, 0).expect(&format!("set_string({s}, 0)"));
 
+        let got = x.cmp_i32(&x);
+        assert_eq!(got, 0, "x = {x}: x.cmp_i32(x)");
+
         let got = x.cmp(&x);
-        assert_eq!(got, 0, "x = {x}: x.cmp(x)");
+        assert_eq!(got, Ordering::Equal, "x = {x}: x.cmp(x)");
+    }
+}
+
+#[test]
+fn int_ord_hash() {
+    let mut values = vec![];
+    for s in CMP_ABS_TESTS.iter() {
+        let x = int_from_str(s, None);
+        let mut y = Int::default();
+        y.neg(&x);
+
+        values.push(x);
+        values.push(y);
+    }
+
+    for x in values.iter() {
+        for y in values.iter() {
+            let want = match x.cmp_i32(y) {
+                -1 => Ordering::Less,
+                0 => Ordering::Equal,
+                _ => Ordering::Greater,
+            };
+            assert_eq!(x.cmp(y), want, "{x}.cmp({y})");
+            assert_eq!(x.partial_cmp(y), Some(want), "{x}.partial_cmp({y})");
+        }
     }
+
+    let mut sorted = values.clone();
+    sorted.sort();
+    for w in sorted.windows(2) {
+        assert!(w[0].cmp_i32(&w[1]) <= 0, "{} and {} not sorted", w[0], w[1]);
+    }
+
+    // "-0" and "0" collapse into a single key
+    let set: HashSet<Int> = values.iter().cloned().collect();
+    assert_eq!(set.len(), values.len() - 1);
+
+    let tree: BTreeSet<Int> = values.iter().cloned().collect();
+    assert_eq!(tree.len(), values.len() - 1);
+    sorted.dedup();
+    assert!(tree.iter().eq(sorted.iter()));
+}
+
+#[test]
+fn is_perfect_power() {
+    // all bᵉ <= 10^6, and those with an odd e, which negative values are limited to
+    let (mut perfect_powers, mut odd_powers) = (BTreeSet::new(), BTreeSet::new());
+    for b in 2i64..=1000 {
+        let (mut p, mut e) = (b * b, 2);
+        while p <= 1_000_000 {
+            perfect_powers.insert(p);
+            if e % 2 == 1 {
+                odd_powers.insert(p);
+            }
+            p *= b;
+            e += 1;
+        }
+    }
+
+    for x in -1_000_000i64..=1_000_000 {
+        let got = Int::new(x).is_perfect_power();
+        let want = if x < 0 {
+            odd_powers.contains(&-x)
+        } else {
+            perfect_powers.contains(&x)
+        };
+        assert_eq!(got.is_some(), want, "is_perfect_power({x})");
+
+        if let Some((b, e)) = got {
+            let mut p = Int::default();
+            p.exp(&b, &Int::new(e as i64), None);
+            assert_eq!(p, Int::new(x), "{b}^{e} != {x}");
+            assert!(b.cmp_abs(&Int::new(1)) > 0, "{x}: base {b}");
+        }
+    }
+
+    let test_vector = vec![
+        ("64", Some(("2", 6))),
+        ("-64", Some(("-4", 3))),
+        ("-16", None),
+        ("-32", Some(("-2", 5))),
+        ("-729", Some(("-9", 3))),
+        ("-1024", Some(("-4", 5))),
+        ("0", None),
+        ("1", None),
+        ("-1", None),
+        ("18446744073709551616", Some(("2", 64))),
+        ("18446744073709551615", None),
+        ("340282366920938463463374607431768211457", None),
+        (
+            "1000000000000000000000000000000000000000000000000000000000000",
+            Some(("10", 60)),
+        ),
+    ];
+
+    for (s, want) in test_vector {
+        let got = int_from_str(s, Some(10)).is_perfect_power();
+        let want = want.map(|(b, e)| (int_from_str(b, Some(10)), e));
+        assert_eq!(got, want, "is_perfect_power({s})");
+    }
+
+    // thousand-digit powers with composite exponents, and their neighbours
+    for (b, e) in [
+        (12345i64, 3 * 5 * 7 * 11),
+        (7, 1183),
+        (1 << 20, 167),
+        (999_999_937, 110),
+    ] {
+        let mut x = Int::default();
+        x.exp(&Int::new(b), &Int::new(e), None);
+
+        let (got_b, got_e) = x.is_perfect_power().expect("perfect power");
+        let mut p = Int::default();
+        p.exp(&got_b, &Int::new(got_e as i64), None);
+        assert_eq!(p, x, "{b}^{e}");
+        assert_eq!(got_e as i64 % e, 0, "{b}^{e}: exponent {got_e}");
+
+        assert_eq!((&x + 1u64).is_perfect_power(), None, "{b}^{e} + 1");
+        assert_eq!((&x - 1u64).is_perfect_power(), None, "{b}^{e} - 1");
+    }
+}
+
+#[test]
+fn is_perfect_square() {
+    for i in -1000i64..100_000 {
+        let want = i >= 0 && {
+            let r = (i as f64).sqrt() as i64;
+            (r - 1..=r + 1).any(|r| r * r == i)
+        };
+        assert_eq!(
+            Int::new(i).is_perfect_square(),
+            want,
+            "is_perfect_square({i})"
+        );
+    }
+
+    let x = int_from_str(
+        &"31415926535897932384626433832795028841971".repeat(25),
+        Some(10),
+    );
+    let mut sq = Int::default();
+    sq.mul(&x, &x);
+    assert!(sq.is_perfect_square());
+    assert!(!(&sq + 1u64).is_perfect_square());
+    assert!(!(&sq - 1u64).is_perfect_square());
+    assert!(!(&sq + &x + &x + 2u64).is_perfect_square());
+    assert!((&sq + &x + &x + 1u64).is_perfect_square());
 }
 
 // ref: https://github.com/golang/go/issues/22830
@@ -937,8 +1915,8 @@ fn jacobi() {
     )];
 
     for (i, c) in test_vector.iter().enumerate() {
-        x.set_string(c.x, 10);
-        y.set_string(c.y, 10);
+        x.set_string(c.x, 10).unwrap();
+        y.set_string(c.y, 10).unwrap();
 
         assert_eq!(
             big::jacobi(&x, &y),
@@ -960,6 +1938,162 @@ fn jacobi_panic() {
     .expect_err("miss error");
 }
 
+#[test]
+fn kronecker() {
+    struct Case {
+        x: i64,
+        y: i64,
+        result: i32,
+    }
+
+    fn new_case(x: i64, y: i64, result: i32) -> Case {
+        Case { x, y, result }
+    }
+
+    let test_vector = vec![
+        new_case(0, 0, 0),
+        new_case(1, 0, 1),
+        new_case(-1, 0, 1),
+        new_case(2, 0, 0),
+        new_case(0, 1, 1),
+        new_case(0, -1, 1),
+        new_case(0, 2, 0),
+        new_case(1, 2, 1),
+        new_case(3, 2, -1),
+        new_case(5, 2, -1),
+        new_case(7, 2, 1),
+        new_case(-1, 2, 1),
+        new_case(-3, 2, -1),
+        new_case(1, -2, 1),
+        new_case(-1, -2, -1),
+        new_case(-1, -1, -1),
+        new_case(2, -1, 1),
+        new_case(3, 4, 1),
+        new_case(3, 8, -1),
+        new_case(5, 8, -1),
+        new_case(-5, 8, -1),
+        new_case(5, -8, -1),
+        new_case(-5, -8, 1),
+        new_case(6, 4, 0),
+        new_case(7, 12, 1),
+        new_case(-7, 12, -1),
+        new_case(11, 24, 1),
+        new_case(11, -24, 1),
+        new_case(-11, -24, 1),
+        new_case(3, 20, -1),
+        new_case(15, 16, 1),
+        new_case(2, 6, 0),
+        new_case(5, 6, 1),
+        new_case(-5, 6, -1),
+        new_case(5, -6, 1),
+        new_case(21, 10, -1),
+        new_case(-21, 10, -1),
+        new_case(3, 18, 0),
+        new_case(13, 56, 1),
+        new_case(-3, 56, -1),
+        new_case(0, 5, 0),
+        new_case(2, 5, -1),
+        new_case(-2, -5, 1),
+        new_case(6, -5, 1),
+        new_case(-6, -5, -1),
+    ];
+
+    let mut x = Int::default();
+    let mut y = Int::default();
+
+    for (i, c) in test_vector.iter().enumerate() {
+        x.set_int64(c.x);
+        y.set_int64(c.y);
+
+        assert_eq!(
+            big::kronecker(&x, &y),
+            c.result,
+            "#{i} kronecker({}, {})",
+            c.x,
+            c.y
+        );
+    }
+
+    struct StringCase {
+        x: &'static str,
+        y: &'static str,
+        result: i32,
+    }
+
+    fn new_string_case(x: &'static str, y: &'static str, result: i32) -> StringCase {
+        StringCase { x, y, result }
+    }
+
+    let test_vector = vec![
+        new_string_case("-9285308306346108245", "110050125563664712232", -1),
+        new_string_case("27855924919038324737", "-27512531390916178058", -1),
+        new_string_case("12345678901234567890121", "-3541774862152233910272", 1),
+        new_string_case("-12345678901234567890121", "-11805916207174113034240", -1),
+    ];
+
+    for (i, c) in test_vector.iter().enumerate() {
+        x.set_string(c.x, 10).unwrap();
+        y.set_string(c.y, 10).unwrap();
+
+        assert_eq!(
+            big::kronecker(&x, &y),
+            c.result,
+            "#{i} string kronecker({}, {})",
+            c.x,
+            c.y
+        );
+    }
+
+    // the Jacobi symbol for odd positive y, and multiplicative in y otherwise
+    for a in -30i64..=30 {
+        let x = Int::new(a);
+        for b in 1i64..=30 {
+            let k = big::kronecker(&x, &Int::new(b));
+            if b % 2 == 1 {
+                assert_eq!(k, big::jacobi(&x, &Int::new(b)), "kronecker({a}, {b})");
+            }
+
+            // (x/0) breaks multiplicativity for |x| = 1
+            for c in (-20i64..=20).filter(|&c| c != 0) {
+                let kc = big::kronecker(&x, &Int::new(c));
+                let kbc = big::kronecker(&x, &Int::new(b * c));
+                assert_eq!(kbc, k * kc, "kronecker({a}, {b}·{c})");
+            }
+        }
+    }
+}
+
+#[test]
+fn legendre() {
+    for p in (3u64..200).filter(|&p| Int::from(p).probably_prime(0)) {
+        let p_int = Int::from(p);
+        let e = Int::from((p - 1) / 2);
+        for a in -300i64..300 {
+            // Euler's criterion: (a/p) ≡ a^((p-1)/2) (mod p)
+            let mut r = Int::default();
+            r.r#mod(&Int::new(a), &p_int);
+            r.exp(&r.clone(), &e, Some(&p_int));
+            let want = match r.uint64() {
+                0 => 0,
+                1 => 1,
+                _ => -1,
+            };
+            assert_eq!(
+                big::legendre(&Int::new(a), &p_int),
+                want,
+                "legendre({a}, {p})"
+            );
+        }
+    }
+}
+
+#[cfg(debug_assertions)]
+#[test]
+#[should_panic(expected = "need odd prime")]
+fn legendre_composite() {
+    big::legendre(&Int::new(2), &Int::new(15));
+}
+
 #[test]
 fn lsh() {
     for (i, c) in LSH_TESTS.iter().enumerate() {
@@ -1029,8 +2163,8 @@ fn mod_inverse() {
     let one = Int::new(1);
 
     for c in test_vector {
-        element.set_string(c.element, 10);
-        modulus.set_string(c.modulus, 10);
+        element.set_string(c.element, 10).unwrap();
+        modulus.set_string(c.modulus, 10).unwrap();
         inverse.mod_inverse(&element, &modulus);
 
         let inv = inverse.clone();
@@ -1055,7 +2189,7 @@ fn mod_sqrt() {
 
     let mut r = helper::rand::Reader::new(9);
     for (i, s) in PRIMES.iter().enumerate().skip(1) {
-        m.set_string(s, 10);
+        m.set_string(s, 10).unwrap();
         modx4.lsh(&m, 2);
 
         for _ in 1..5 {
@@ -1173,6 +2307,56 @@ fn mul_range_z() {
     }
 }
 
+#[test]
+fn next_prev_prime() {
+    const N: usize = 10_000;
+
+    let mut sieve = vec![true; N];
+    sieve[0] = false;
+    sieve[1] = false;
+    for i in 2..N {
+        if sieve[i] {
+            for j in ((i * i)..N).step_by(i) {
+                sieve[j] = false;
+            }
+        }
+    }
+
+    for n in -5..(N as i64 - 100) {
+        let want = (n.max(1) as usize + 1..N).find(|&p| sieve[p]).unwrap();
+        assert_eq!(Int::new(n).next_prime(), Int::from(want), "next_prime({n})");
+
+        let want = (0..n.max(0) as usize)
+            .rev()
+            .find(|&p| sieve[p])
+            .map(Int::from);
+        assert_eq!(Int::new(n).prev_prime(), want, "prev_prime({n})");
+    }
+
+    // (e, next_prime(2^e) - 2^e, 2^e - prev_prime(2^e))
+    let test_vector = vec![
+        (32usize, 15i64, 5i64),
+        (64, 13, 59),
+        (128, 51, 159),
+        (256, 297, 189),
+        (512, 75, 569),
+        (1024, 643, 105),
+    ];
+    for (e, next, prev) in test_vector {
+        let x = Int::new(1) << e;
+        let (p, q) = (&x + next, &x - prev);
+        assert_eq!(x.next_prime(), p, "next_prime(2^{e})");
+        assert_eq!(x.prev_prime(), Some(q.clone()), "prev_prime(2^{e})");
+        assert_eq!(q.next_prime(), p, "next_prime(2^{e}-{prev})");
+        assert_eq!(p.prev_prime(), Some(q), "prev_prime(2^{e}+{next})");
+    }
+
+    // the Mersenne prime 2^521-1
+    let m = (Int::new(1) << 521usize) - 1i64;
+    assert_eq!((&m - 1i64).next_prime(), m);
+    assert_eq!((&m + 1i64).prev_prime(), Some(m));
+}
+
 #[test]
 fn not() {
     struct Case {
@@ -1199,8 +2383,8 @@ fn not() {
     let mut output = Int::default();
     let mut expected = Int::default();
     for (i, c) in test_vector.iter().enumerate() {
-        input.set_string(c.input, 10);
-        expected.set_string(c.output, 10);
+        input.set_string(c.input, 10).unwrap();
+        expected.set_string(c.output, 10).unwrap();
         output.not(&input);
         assert_eq!(output, expected, "#{i} 1st not");
 
@@ -1209,6 +2393,84 @@ fn not() {
     }
 }
 
+#[test]
+fn primorial() {
+    let test_vector = vec![
+        (0, "1"),
+        (1, "1"),
+        (2, "2"),
+        (3, "6"),
+        (4, "6"),
+        (10, "210"),
+        (30, "6469693230"),
+        (100, "2305567963945518424753102147331756070"),
+    ];
+
+    let mut z = Int::default();
+    for (n, want) in test_vector {
+        assert_eq!(z.primorial(n).to_string(), want, "{n}#");
+    }
+
+    let mut want = Int::new(1);
+    for n in 2..3000u64 {
+        if Int::from(n).probably_prime(0) {
+            want = &want * n;
+        }
+        assert_eq!(*z.primorial(n), want, "{n}#");
+    }
+}
+
+#[test]
+fn probably_prime() {
+    let nreps = 20;
+
+    for (i, s) in PRIMES.iter().enumerate() {
+        let p = int_from_decimal_str(s);
+        assert!(
+            p.probably_prime(nreps) && p.probably_prime(1) && p.probably_prime(0),
+            "#{i} prime found to be non-prime ({s})"
+        );
+    }
+
+    for (i, s) in COMPOSITES.iter().enumerate() {
+        let s: String = s.chars().filter(|c| !c.is_whitespace()).collect();
+        let c = int_from_decimal_str(&s);
+        assert!(
+            !c.probably_prime(nreps) && !c.probably_prime(1) && !c.probably_prime(0),
+            "#{i} composite found to be prime ({s})"
+        );
+    }
+
+    // negative values are never prime
+    for (i, s) in PRIMES.iter().enumerate() {
+        let mut p = int_from_decimal_str(s);
+        p.neg(&p.clone());
+        assert!(!p.probably_prime(nreps), "#{i} negative prime -{s}");
+    }
+}
+
+#[test]
+fn probably_prime_small() {
+    const N: usize = 100_000;
+
+    let mut sieve = vec![true; N];
+    sieve[0] = false;
+    sieve[1] = false;
+    for i in 2..N {
+        if sieve[i] {
+            for j in ((i * i)..N).step_by(i) {
+                sieve[j] = false;
+            }
+        }
+    }
+
+    let mut x = Int::default();
+    for (i, &want) in sieve.iter().enumerate() {
+        x.set_int64(i as i64);
+        assert_eq!(x.probably_prime(0), want, "probably_prime({i})");
+    }
+}
+
 #[test]
 fn prod_zz() {
     fn mul_zz<'a>(z: &'a mut Int, x: &Int, y: &Int) -> &'a mut Int {
@@ -1256,7 +2518,7 @@ fn quo() {
 
     fn int_from_decimal_string(s: &str) -> Int {
         let mut out = Int::default();
-        out.set_string(s, 10);
+        out.set_string(s, 10).unwrap();
         out
     }
 
@@ -1282,7 +2544,7 @@ fn quo_step_d6() {
     let u = {
         let mut v = Int::default();
 
-        let abs = [0, 0, 0, 0, 1, 1 << 31, u32::MAX, u32::MAX ^ (1 << 31)];
+        let abs = [0, 0, 1 + (1 << (W - 1)), Word::MAX ^ (1 << (W - 1))];
 
         v.set_bits(&abs);
         v
@@ -1291,7 +2553,7 @@ fn quo_step_d6() {
     let v = {
         let mut v = Int::default();
 
-        let abs = [5, 0, 2, 1 << 31, 0, 1 << 31];
+        let abs = [5, 2 + (1 << (W - 1)), 1 << (W - 1)];
 
         v.set_bits(&abs);
         v
@@ -1301,15 +2563,165 @@ fn quo_step_d6() {
     let mut q = Int::default();
     q.quo_rem(&u, &v, &mut r);
 
-    const EXPECTED_Q64: &'static str = "18446744073709551613";
-    const EXPECTED_R64: &'static str = "3138550867693340382088035895064302439801311770021610913807";
-    //const EXPECTED_Q32: &'static str = "4294967293";
-    //const EXPECTED_R32: &'static str = "39614081266355540837921718287";
+    const EXPECTED_Q64: &str = "18446744073709551613";
+    const EXPECTED_R64: &str = "3138550867693340382088035895064302439801311770021610913807";
+    const EXPECTED_Q32: &str = "4294967293";
+    const EXPECTED_R32: &str = "39614081266355540837921718287";
+
+    let (expected_q, expected_r) = if W == 32 {
+        (EXPECTED_Q32, EXPECTED_R32)
+    } else {
+        (EXPECTED_Q64, EXPECTED_R64)
+    };
+
+    assert_eq!(q.to_string(), expected_q, "bad q");
+    assert_eq!(r.to_string(), expected_r, "bad r");
+}
+
+#[test]
+fn rand() {
+    let mut r = helper::rand::Reader::new(13);
+
+    // every residue must show up equally often, including for moduli below 256 and
+    // moduli whose top byte is only partially used
+    for n in [2u64, 3, 7, 10, 200, 255, 256, 257, 1000] {
+        let mut counts = vec![0u64; n as usize];
+        let n = Int::from(n);
+
+        let mut z = Int::default();
+        for _ in 0..counts.len() * 100 {
+            z.rand(&mut r, &n);
+            assert!(z.sign() >= 0 && z < n, "{z} not in [0, {n})");
+            counts[z.uint64() as usize] += 1;
+        }
+        assert_uniform(&counts, &format!("rand(_, {n})"));
+    }
+
+    // buckets of the leading bits for a wide modulus just above a power of two
+    let n = int_from_str("0x100000000000000000001", Some(0));
+    let mut counts = vec![0u64; 32];
+    let mut z = Int::default();
+    for _ in 0..counts.len() * 400 {
+        z.rand(&mut r, &n);
+        assert!(z.sign() >= 0 && z < n, "{z} not in [0, {n})");
+        let mut q = Int::default();
+        q.mul(&z, &Int::new(counts.len() as i64));
+        q.quo(&q.clone(), &n);
+        counts[q.uint64() as usize] += 1;
+    }
+    assert_uniform(&counts, "rand(_, 2^80 + 1)");
+
+    for n in [0i64, 1, -1, -1000] {
+        let mut z = Int::new(42);
+        z.rand(&mut r, &Int::new(n));
+        assert_eq!(z, 0i64, "rand(_, {n})");
+    }
+}
+
+#[test]
+fn rand_bits() {
+    let mut r = helper::rand::Reader::new(17);
+
+    for bits in [0usize, 1, 5, 8, 9, 64, 100] {
+        let mut ones = vec![0u64; bits];
+        let mut z = Int::default();
+        for _ in 0..2000 {
+            z.rand_bits(&mut r, bits);
+            assert!(
+                z.sign() >= 0 && z.bit_len() <= bits,
+                "{z} has more than {bits} bits"
+            );
+            for (i, v) in ones.iter_mut().enumerate() {
+                *v += z.bit(i) as u64;
+            }
+        }
+
+        for (i, &v) in ones.iter().enumerate() {
+            assert_uniform(&[v, 2000 - v], &format!("bit {i} of rand_bits(_, {bits})"));
+        }
+    }
+}
+
+#[test]
+fn rand_range() {
+    let mut r = helper::rand::Reader::new(19);
+
+    let test_vector = vec![
+        (-5i64, 5i64),
+        (0, 1),
+        (-3, -2),
+        (1000, 1013),
+        (-(1 << 40), -(1 << 40) + 7),
+    ];
+
+    for (lo, hi) in test_vector {
+        let (x, y) = (Int::new(lo), Int::new(hi));
+
+        let mut counts = vec![0u64; (hi - lo) as usize];
+        let mut z = Int::default();
+        for _ in 0..counts.len() * 400 {
+            z.rand_range(&mut r, &x, &y);
+            assert!(z >= x && z < y, "{z} not in [{lo}, {hi})");
+            counts[(z.int64() - lo) as usize] += 1;
+        }
+        assert_uniform(&counts, &format!("rand_range(_, {lo}, {hi})"));
+    }
+}
+
+#[test]
+#[should_panic(expected = "empty range")]
+fn rand_range_empty() {
+    let mut r = helper::rand::Reader::new(19);
+    Int::default().rand_range(&mut r, &Int::new(1), &Int::new(1));
+}
+
+#[test]
+fn root() {
+    for n in 1u32..=9 {
+        for i in 0i64..3000 {
+            let mut r = Int::new(-2);
+            r.root(&Int::new(i), n);
+
+            // r^n <= i < (r+1)^n
+            let r = r.int64();
+            assert!(r >= 0, "root({i}, {n}) = {r}");
+            assert!(
+                (r as f64).powi(n as i32) <= i as f64,
+                "root({i}, {n}) = {r}"
+            );
+            assert!(
+                ((r + 1) as f64).powi(n as i32) > i as f64,
+                "root({i}, {n}) = {r}"
+            );
 
-    assert_eq!(q.to_string(), EXPECTED_Q64, "bad q64");
-    //assert_eq!(q.to_string(), EXPECTED_Q32, "bad q32");
-    assert_eq!(r.to_string(), EXPECTED_R64, "bad r64");
-    //assert_eq!(r.to_string(), EXPECTED_R32, "bad r32");
+            if n % 2 == 1 {
+                let mut neg = Int::default();
+                neg.root(&Int::new(-i), n);
+                assert_eq!(neg, Int::new(-r), "root({}, {n})", -i);
+            }
+        }
+    }
+
+    // (b^n - 1, b - 1), (b^n, b) and (b^n + 1, b) for large b
+    let b = int_from_str(&"27182818284590452353602874713527".repeat(10), Some(10));
+    for n in [2u32, 3, 5, 17, 64] {
+        let mut x = Int::default();
+        x.exp(&b, &Int::new(n as i64), None);
+
+        let mut r = Int::default();
+        r.root(&x, n);
+        assert_eq!(r, b, "root(b^{n}, {n})");
+        r.root(&(&x + 1u64), n);
+        assert_eq!(r, b, "root(b^{n} + 1, {n})");
+        r.root(&(&x - 1u64), n);
+        assert_eq!(r, &b - 1u64, "root(b^{n} - 1, {n})");
+    }
+}
+
+#[test]
+#[should_panic(expected = "even root of a negative number")]
+fn root_negative() {
+    Int::default().root(&Int::new(-4), 2);
 }
 
 #[test]
@@ -1335,13 +2747,49 @@ fn set_bytes() {
     }
 }
 
+#[test]
+fn set_float64() {
+    let test_vector = vec![
+        (0.0, Some("0")),
+        (-0.0, Some("0")),
+        (0.5, Some("0")),
+        (-0.5, Some("0")),
+        (1.5, Some("1")),
+        (-1.5, Some("-1")),
+        (1e20, Some("100000000000000000000")),
+        (-9007199254740993.0, Some("-9007199254740992")),
+        (
+            f64::MAX,
+            Some("179769313486231570814527423731704356798070567525844996598917476803157260780028538760589558632766878171540458953514382464234321326889464182768467546703537516986049910576551282076245490090389328944075868508455133942304583236903222948165808559332123348274797826204144723168738177180919299881250404026184124858368"),
+        ),
+        (f64::NAN, None),
+        (f64::INFINITY, None),
+        (f64::NEG_INFINITY, None),
+    ];
+
+    for (i, &(f, want)) in test_vector.iter().enumerate() {
+        let mut z = Int::new(1234567890);
+        let got = z.set_float64(f).map(|v| v.to_string());
+        assert_eq!(got.as_deref(), want, "#{i} set_float64({f})");
+
+        if want.is_none() {
+            assert_eq!(z, Int::new(1234567890), "#{i} set_float64({f}) changed z");
+        } else {
+            assert!(
+                is_normalized(&z),
+                "#{i} set_float64({f}): {z} is not normalized"
+            );
+        }
+    }
+}
+
 #[test]
 fn set_z() {
     for a in SUM_ZZ.iter() {
         let mut z = Int::default();
         z.set(&a.z);
         assert!(is_normalized(&z), "{z} is not normalized");
-        assert!(z.cmp(&a.z) == 0, "got z={}; want {}", z, a.z);
+        assert!(z.cmp_i32(&a.z) == 0, "got z={}; want {}", z, a.z);
     }
 }
 
@@ -1350,7 +2798,7 @@ fn sign_z() {
     let zero = Int::default();
     for a in SUM_ZZ.iter() {
         let s = a.z.sign();
-        let e = a.z.cmp(&zero);
+        let e = a.z.cmp_i32(&zero);
         assert_eq!(s, e, "z = {}", a.z);
     }
 }
@@ -1446,6 +2894,29 @@ fn trailing_zero_bits() {
     }
 }
 
+#[test]
+fn uint32() {
+    let test_vector = vec![
+        // uint32
+        ("0", Some(0)),
+        ("1", Some(1)),
+        ("4294967295", Some(u32::MAX)),
+        // not uint32
+        ("-1", None),
+        ("4294967296", None),
+        ("-4294967296", None),
+    ];
+
+    for (s, want) in test_vector {
+        let x = int_from_str(s, None);
+        assert_eq!(x.is_uint32(), want.is_some(), "is_uint32({s})");
+        assert_eq!(x.checked_uint32(), want, "checked_uint32({s})");
+        if let Some(v) = want {
+            assert_eq!(x.uint32(), v, "uint32({s})");
+        }
+    }
+}
+
 #[test]
 fn uint64() {
     let test_vector = vec![
@@ -1467,7 +2938,7 @@ fn uint64() {
 
     for s in test_vector {
         let mut x = Int::default();
-        assert!(x.set_string(s, 0).is_some(), "set_string({s}, 0) failed");
+        assert!(x.set_string(s, 0).is_ok(), "set_string({s}, 0) failed");
 
         let want = match strconv::parse_uint(s, 0, 64) {
             Ok(v) => v,
@@ -1480,6 +2951,7 @@ fn uint64() {
 
                 if ok {
                     assert!(!x.is_uint64(), "is_uint64({s}) succeed unexpectedly");
+                    assert_eq!(x.checked_uint64(), None, "checked_uint64({s})");
                 } else {
                     panic!("parse_uint({s}) failed");
                 }
@@ -1489,6 +2961,7 @@ fn uint64() {
 
         assert!(x.is_uint64(), "is_uint64({s}) failed unexpectedly");
         assert_eq!(x.uint64(), want, "uint64({s})");
+        assert_eq!(x.checked_uint64(), Some(want), "checked_uint64({s})");
     }
 }
 
@@ -1498,7 +2971,7 @@ fn alt_bit(x: &Int, i: usize) -> u8 {
     z.rsh(x, i);
     z.and(&z.clone(), &Int::new(1));
 
-    if z.cmp(&Int::new(0)) != 0 {
+    if z.cmp_i32(&Int::new(0)) != 0 {
         1
     } else {
         0
@@ -1519,6 +2992,29 @@ fn alt_set_bit(z: Int, x: &Int, i: usize, b: bool) -> Int {
     z
 }
 
+/// Asserts by a chi-squared test that `counts` look uniformly distributed. The bound is
+/// about 5 standard deviations above the mean of the statistic, so that a correct
+/// implementation virtually never fails.
+fn assert_uniform(counts: &[u64], msg: &str) {
+    if counts.len() < 2 {
+        return;
+    }
+
+    let total: u64 = counts.iter().sum();
+    let expected = total as f64 / counts.len() as f64;
+    let chi2: f64 = counts
+        .iter()
+        .map(|&c| (c as f64 - expected).powi(2) / expected)
+        .sum();
+
+    let df = (counts.len() - 1) as f64;
+    let bound = df + 5.0 * (2.0 * df).sqrt();
+    assert!(
+        chi2 < bound,
+        "{msg}: chi2 = {chi2:.2} >= {bound:.2}, counts = {counts:?}"
+    );
+}
+
 fn check_bytes(b: &[u8]) {
     let b = {
         let mut v = b;
@@ -1587,7 +3083,7 @@ fn check_quo(x: &[u8], y: &[u8]) {
     let mut q = Int::default();
     q.quo_rem(&u, &v, &mut r);
 
-    assert!(r.cmp(&v) < 0, "remainder not less than divisor");
+    assert!(r.cmp_i32(&v) < 0, "remainder not less than divisor");
 
     let uprime = {
         let mut out = q.clone();
@@ -1662,7 +3158,7 @@ fn mul_bytes(x: &[u8], y: &[u8]) -> Vec<u8> {
     b.to_vec()
 }
 
-fn norm(x: &[u32]) -> Vec<u32> {
+fn norm(x: &[Word]) -> Vec<Word> {
     let mut i = x.len();
     while (i > 0) && (x[i - 1] == 0) {
         i -= 1;
@@ -1689,7 +3185,7 @@ where
     F: for<'a> Fn(&'a mut Int, &Int, &Int) -> &'a mut Int,
 {
     let mut expected = Int::default();
-    expected.set_string(exp, 0);
+    expected.set_string(exp, 0).unwrap();
 
     let mut got = Int::default();
     let _ = f(&mut got, x, y);
@@ -1787,7 +3283,7 @@ fn test_mod_sqrt(elt: &Int, m: &Int, sq: &mut Int, sqrt: &mut Int) -> bool {
         "mod_sqrt returned inconsistent value {z} after sub"
     );
 
-    if sqrt.cmp(elt) == 0 {
+    if sqrt.cmp_i32(elt) == 0 {
         return true;
     }
 
@@ -1795,5 +3291,5 @@ fn test_mod_sqrt(elt: &Int, m: &Int, sq: &mut Int, sqrt: &mut Int) -> bool {
     sqrt_sq.mul(&sqrt, &sqrt);
     sqrt_sq.r#mod(&sqrt_sq.clone(), m);
 
-    sq.cmp(&sqrt_sq) == 0
+    sq.cmp_i32(&sqrt_sq) == 0
 }
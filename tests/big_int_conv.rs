@@ -1,4 +1,6 @@
-use math::big::Int;
+use std::str::FromStr;
+
+use math::big::{Int, ParseIntError, TryFromIntError, MAX_BASE};
 
 mod helper;
 
@@ -66,6 +68,7 @@ lazy_static::lazy_static! {
     StringTest::new("A", "A", 37, 36, true),
     StringTest::new("ABCXYZ", "abcxyz", 36, 623741435, true),
     StringTest::new("ABCXYZ", "ABCXYZ", 62, 33536793425, true),
+    StringTest::new("zA", "zA", 37, 35*37+36, true),
 
     // valid input with separators
     // (smoke tests only - a comprehensive set of tests is in natconv_test.go)
@@ -112,6 +115,46 @@ fn append_text() {
     }
 }
 
+#[test]
+fn from_primitive() {
+    assert_eq!(Int::from(i8::MIN).to_string(), "-128");
+    assert_eq!(Int::from(i16::MIN).to_string(), "-32768");
+    assert_eq!(Int::from(i32::MIN).to_string(), "-2147483648");
+    assert_eq!(Int::from(i64::MIN).to_string(), "-9223372036854775808");
+    assert_eq!(
+        Int::from(i128::MIN).to_string(),
+        "-170141183460469231731687303715884105728"
+    );
+    assert_eq!(Int::from(-1isize).to_string(), "-1");
+    assert_eq!(Int::from(u8::MAX).to_string(), "255");
+    assert_eq!(Int::from(u16::MAX).to_string(), "65535");
+    assert_eq!(Int::from(u32::MAX).to_string(), "4294967295");
+    assert_eq!(Int::from(u64::MAX).to_string(), "18446744073709551615");
+    assert_eq!(
+        Int::from(u128::MAX).to_string(),
+        "340282366920938463463374607431768211455"
+    );
+    assert_eq!(Int::from(0usize).to_string(), "0");
+}
+
+#[test]
+fn from_str() {
+    for (i, c) in STRING_TESTS.iter().enumerate().filter(|(_, c)| c.base == 0) {
+        let got = Int::from_str(c.input);
+        assert_eq!(got.is_ok(), c.ok, "#{i} (input '{}')", c.input);
+
+        if let Ok(v) = got {
+            assert!(is_normalized(&v), "#{i} (input '{}')", c.input);
+            assert_eq!(v, Int::new(c.val), "#{i} (input '{}')", c.input);
+        }
+    }
+
+    let got: Int = "-0x1_0000_0000_0000_0000_0000_0000_0000_0000"
+        .parse()
+        .unwrap();
+    assert_eq!(got, Int::from(i128::MIN) * 2u32);
+}
+
 #[test]
 fn get_string() {
     let mut z = Int::default();
@@ -144,6 +187,96 @@ fn get_string() {
     }
 }
 
+#[test]
+fn scan() {
+    use ParseIntError::*;
+
+    // (input, base, value, actual base, bytes read, rest)
+    let test_vector = vec![
+        ("0", 0, "0", 10, 1, ""),
+        ("-0", 0, "0", 10, 2, ""),
+        ("08", 0, "0", 10, 1, "8"),
+        ("0 1", 0, "0", 10, 1, " 1"),
+        ("07", 0, "7", 8, 2, ""),
+        ("0_7", 0, "7", 8, 3, ""),
+        ("0b_1010 ", 0, "10", 2, 7, " "),
+        ("+0o660,", 0, "432", 8, 6, ","),
+        ("-0xF00D_1Eg", 0, "-15731998", 16, 10, "g"),
+        ("0XaBc", 0, "2748", 16, 5, ""),
+        ("1_000_000x", 0, "1000000", 10, 9, "x"),
+        ("123abc", 10, "123", 10, 3, "abc"),
+        ("123abc", 16, "1194684", 16, 6, ""),
+        ("0x10", 16, "0", 16, 1, "x10"),
+        ("1_000", 10, "1", 10, 1, "_000"),
+        ("-ff\n", 16, "-255", 16, 3, "\n"),
+        ("zZ", 62, "2231", 62, 2, ""),
+        ("1\u{e9}", 0, "1", 10, 1, "\u{e9}"),
+    ];
+
+    for (i, &(input, base, value, want_base, want_n, rest)) in test_vector.iter().enumerate() {
+        let mut r = std::io::Cursor::new(input.as_bytes());
+        let mut z = Int::default();
+
+        let (b, n) = z.scan(&mut r, base).expect("scan");
+        assert_eq!(z.string(), value, "#{i} scan({input:?}, {base})");
+        assert_eq!((b, n), (want_base, want_n), "#{i} scan({input:?}, {base})");
+        assert_eq!(&input[n..], rest, "#{i} scan({input:?}, {base})");
+        assert_eq!(r.position() as usize, n, "#{i} scan({input:?}, {base})");
+        assert!(is_normalized(&z), "#{i} {z} is not normalized");
+    }
+
+    let test_vector = vec![
+        ("", 0, Empty),
+        ("-", 0, Empty),
+        ("+x", 10, Empty),
+        ("0x", 0, Empty),
+        ("0bz", 0, Empty),
+        ("g", 16, Empty),
+        ("1", 1, InvalidBase),
+        ("1", 63, InvalidBase),
+        ("_", 0, BadUnderscore { index: 0 }),
+        ("_0", 0, BadUnderscore { index: 0 }),
+        ("0_", 0, BadUnderscore { index: 1 }),
+        ("-1__0", 0, BadUnderscore { index: 3 }),
+        ("0x10_ ", 0, BadUnderscore { index: 4 }),
+        ("0x_", 0, BadUnderscore { index: 2 }),
+    ];
+
+    for (i, &(input, base, want)) in test_vector.iter().enumerate() {
+        let mut z = Int::default();
+        let err = z
+            .scan(&mut input.as_bytes(), base)
+            .expect_err("scan should fail");
+        let got = err
+            .get_ref()
+            .and_then(|v| v.downcast_ref::<ParseIntError>());
+        assert_eq!(got, Some(&want), "#{i} scan({input:?}, {base})");
+    }
+}
+
+#[test]
+fn scan_buffered() {
+    use std::io::{BufRead, BufReader};
+
+    // digits straddling the internal buffer boundaries must still be read as one number
+    let input = format!("-{}0_1 tail", "12_34".repeat(100));
+    let mut r = BufReader::with_capacity(3, input.as_bytes());
+
+    let mut z = Int::default();
+    let (b, n) = z.scan(&mut r, 0).unwrap();
+    assert_eq!((b, n), (10, input.len() - " tail".len()));
+    assert_eq!(z.string(), format!("-{}01", "1234".repeat(100)));
+
+    let mut rest = String::new();
+    r.read_line(&mut rest).unwrap();
+    assert_eq!(rest, " tail");
+
+    assert_eq!(
+        z.scan(&mut r, 0).unwrap_err().kind(),
+        std::io::ErrorKind::InvalidData
+    );
+}
+
 #[test]
 fn set_string() {
     let mut tmp = Int::default();
@@ -151,9 +284,9 @@ fn set_string() {
         tmp.set_int64(1234567890);
 
         let mut n1 = Int::default();
-        let ok1 = n1.set_string(c.input, c.base).is_some();
+        let ok1 = n1.set_string(c.input, c.base).is_ok();
 
-        let ok2 = tmp.set_string(c.input, c.base).is_some();
+        let ok2 = tmp.set_string(c.input, c.base).is_ok();
 
         assert_eq!(
             ok1, c.ok,
@@ -227,6 +360,205 @@ fn set_string() {
     }
 }
 
+#[test]
+fn set_string_errors() {
+    use ParseIntError::*;
+
+    let test_vector = vec![
+        ("", 0, Empty),
+        ("-", 0, Empty),
+        ("+", 10, Empty),
+        ("0x", 0, Empty),
+        ("-0b", 0, Empty),
+        ("10", 1, InvalidBase),
+        ("10", 63, InvalidBase),
+        ("", 255, InvalidBase),
+        (
+            "a",
+            0,
+            InvalidDigit {
+                index: 0,
+                byte: b'a',
+            },
+        ),
+        (
+            "0b2",
+            0,
+            InvalidDigit {
+                index: 2,
+                byte: b'2',
+            },
+        ),
+        (
+            "-08",
+            0,
+            InvalidDigit {
+                index: 2,
+                byte: b'8',
+            },
+        ),
+        (
+            "0xg",
+            0,
+            InvalidDigit {
+                index: 2,
+                byte: b'g',
+            },
+        ),
+        (
+            "12 ",
+            10,
+            InvalidDigit {
+                index: 2,
+                byte: b' ',
+            },
+        ),
+        (
+            "1_000",
+            10,
+            InvalidDigit {
+                index: 1,
+                byte: b'_',
+            },
+        ),
+        (
+            "1\u{e9}",
+            0,
+            InvalidDigit {
+                index: 1,
+                byte: 0xc3,
+            },
+        ),
+        (
+            "+\u{ff11}",
+            16,
+            InvalidDigit {
+                index: 1,
+                byte: 0xef,
+            },
+        ),
+        (
+            "\u{80}",
+            62,
+            InvalidDigit {
+                index: 0,
+                byte: 0xc2,
+            },
+        ),
+        ("_", 0, BadUnderscore { index: 0 }),
+        ("_0", 0, BadUnderscore { index: 0 }),
+        ("0_", 0, BadUnderscore { index: 1 }),
+        ("-1__0", 0, BadUnderscore { index: 3 }),
+        ("0x10_", 0, BadUnderscore { index: 4 }),
+        ("+0x_", 0, BadUnderscore { index: 3 }),
+    ];
+
+    for (i, &(input, base, want)) in test_vector.iter().enumerate() {
+        let mut z = Int::default();
+        let got = z.set_string(input, base).map(|v| v.clone());
+        assert_eq!(got, Err(want), "#{i} set_string({input:?}, {base})");
+    }
+
+    assert_eq!(
+        Int::from_str("0b12"),
+        Err(InvalidDigit {
+            index: 3,
+            byte: b'2'
+        })
+    );
+    assert_eq!(
+        InvalidDigit {
+            index: 3,
+            byte: b'2'
+        }
+        .to_string(),
+        "bad digit '2' at index 3"
+    );
+    assert_eq!(
+        InvalidDigit {
+            index: 1,
+            byte: 0xc3
+        }
+        .to_string(),
+        "bad digit 0xc3 at index 1"
+    );
+    assert_eq!(BadUnderscore { index: 4 }.to_string(), "bad '_' at index 4");
+}
+
+#[test]
+fn set_string_large() {
+    let mut r = helper::rand::Reader::new(2);
+    let mut buf = vec![0u8; 8 * 300];
+    std::io::Read::read_exact(&mut r, &mut buf).unwrap();
+
+    for base in 2..=MAX_BASE {
+        for n in [1usize, 8 * 8 - 1, 8 * 8 + 1, 8 * 33, 8 * 300] {
+            let mut x = Int::default();
+            x.set_bytes(&buf[..n]);
+            x = -x;
+
+            let s = x.text(base);
+            let mut y = Int::default();
+            y.set_string(&s, base).unwrap();
+            assert_eq!(y, x, "base {base}, {n} bytes");
+            assert!(is_normalized(&y), "base {base}, {n} bytes");
+
+            // leading zeros must not change the value
+            let s = format!("-{}{}", "0".repeat(n), &s[1..]);
+            y.set_string(&s, base).unwrap();
+            assert_eq!(y, x, "base {base}, {n} bytes with leading zeros");
+        }
+    }
+
+    // separators between every digit, with and without a base prefix
+    let mut x = Int::default();
+    x.set_bytes(&buf);
+    for (base, prefix) in [(10, ""), (8, "0o"), (16, "0x"), (2, "0b")] {
+        let digits = x.text(base);
+        let s: Vec<String> = digits.chars().map(String::from).collect();
+        let s = format!("{prefix}{}", s.join("_"));
+
+        let mut y = Int::default();
+        y.set_string(&s, 0).unwrap();
+        assert_eq!(y, x, "base {base}");
+
+        let mut rd = s.as_bytes();
+        assert_eq!(y.scan(&mut rd, 0).unwrap(), (base, s.len()));
+        assert_eq!(y, x, "scan base {base}");
+    }
+
+    // 10^k - 1 for k across the leaf and power boundaries
+    for k in [151usize, 152, 153, 304, 305, 1216, 1217, 5000] {
+        let mut x = Int::default();
+        x.exp(&Int::new(10), &Int::new(k as i64), None);
+        x -= 1u64;
+
+        let mut y = Int::default();
+        y.set_string(&"9".repeat(k), 10).unwrap();
+        assert_eq!(y, x, "10^{k} - 1");
+    }
+}
+
+#[test]
+fn set_string_never_panics() {
+    let mut z = Int::default();
+    for base in 0..=u8::MAX {
+        for c in (0..0x800u32)
+            .chain([0xffff, 0x10ffff])
+            .filter_map(char::from_u32)
+        {
+            for s in [
+                c.to_string(),
+                format!("1{c}"),
+                format!("-0x{c}"),
+                format!("0_{c}"),
+            ] {
+                let _ = z.set_string(&s, base);
+            }
+        }
+    }
+}
+
 #[test]
 fn text() {
     let mut z = Int::default();
@@ -240,3 +572,108 @@ fn text() {
         assert_eq!(&got, c.output, "{c:?}");
     }
 }
+
+#[test]
+fn text_large() {
+    // digit-by-digit reference conversion
+    fn naive_text(x: &Int, base: u8) -> String {
+        const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+        let mut v = x.clone();
+        v.abs(x);
+        let mut out = vec![];
+        while v.sign() != 0 {
+            out.push(DIGITS[(&v % base as u64).uint64() as usize]);
+            v = &v / base as u64;
+        }
+        if out.is_empty() {
+            out.push(b'0');
+        }
+        if x.sign() < 0 {
+            out.push(b'-');
+        }
+        out.reverse();
+        String::from_utf8(out).unwrap()
+    }
+
+    let mut r = helper::rand::Reader::new(1);
+    let mut buf = vec![0u8; 8 * 150];
+    std::io::Read::read_exact(&mut r, &mut buf).unwrap();
+
+    for base in 2..=MAX_BASE {
+        let b = Int::new(base as i64);
+
+        let mut xs = vec![];
+        for n in [1usize, 8 * 8, 8 * 9 + 3, 8 * 40, 8 * 150] {
+            let mut x = Int::default();
+            x.set_bytes(&buf[..n]);
+            xs.push(x);
+        }
+        // powers of the base and their predecessors yield long runs of '0's and
+        // maximal digits across the block boundaries
+        for k in [20i64, 160, 161, 1000, 2999] {
+            let mut x = Int::default();
+            x.exp(&b, &Int::new(k), None);
+            xs.push(&x - 1u64);
+            xs.push(x);
+        }
+
+        for x in xs {
+            for x in [-&x, x] {
+                let got = x.text(base);
+                assert_eq!(got, naive_text(&x, base), "base {base}");
+
+                let mut y = Int::default();
+                y.set_string(&got, base).unwrap();
+                assert_eq!(y, x, "base {base}");
+            }
+        }
+    }
+}
+
+#[test]
+fn text_threads() {
+    let mut x = Int::default();
+    x.exp(&Int::new(3), &Int::new(40000), None);
+    let want: Vec<String> = [3u8, 10, 62].iter().map(|&b| x.text(b)).collect();
+
+    let handles: Vec<_> = (0..8)
+        .map(|i| {
+            let x = x.clone();
+            std::thread::spawn(move || {
+                let mut y = Int::default();
+                y.exp(&Int::new(7), &Int::new(10000 + i), None);
+                let _ = y.text([3, 10, 62][i as usize % 3]);
+                [3u8, 10, 62].map(|b| x.text(b))
+            })
+        })
+        .collect();
+
+    for h in handles {
+        assert_eq!(h.join().unwrap().to_vec(), want);
+    }
+}
+
+#[test]
+fn try_from() {
+    macro_rules! check {
+        ($($t:ty)*) => {$(
+            let (min, max) = (Int::from(<$t>::MIN), Int::from(<$t>::MAX));
+            assert_eq!(<$t>::try_from(&min), Ok(<$t>::MIN), "{}::MIN", stringify!($t));
+            assert_eq!(<$t>::try_from(max.clone()), Ok(<$t>::MAX), "{}::MAX", stringify!($t));
+
+            let under = min - 1u32;
+            let over = max + 1u32;
+            assert!(<$t>::try_from(&under).is_err(), "{}::MIN-1", stringify!($t));
+            assert!(<$t>::try_from(&over).is_err(), "{}::MAX+1", stringify!($t));
+        )*};
+    }
+
+    check!(i8 i16 i32 i64 i128 isize u8 u16 u32 u64 u128 usize);
+
+    let err: TryFromIntError = u8::try_from(Int::new(-1)).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "out of range integral type conversion attempted"
+    );
+}
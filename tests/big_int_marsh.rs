@@ -0,0 +1,153 @@
+use std::str::FromStr;
+
+use math::big::{Int, UnmarshalError};
+
+mod helper;
+
+use helper::is_big_int_normalized as is_normalized;
+
+lazy_static::lazy_static! {
+  // (decimal text, hex of the gob encoding) as produced by Go's math/big
+  static ref ENCODING_TESTS: Vec<(&'static str, &'static str)> = vec![
+    ("0", "02"),
+    ("1", "0201"),
+    ("-1", "0301"),
+    ("2", "0202"),
+    ("-2", "0302"),
+    ("10", "020a"),
+    ("-10", "030a"),
+    ("42", "022a"),
+    ("-42", "032a"),
+    ("1234567890", "02499602d2"),
+    ("-1234567890", "03499602d2"),
+    (
+      "298472983472983471903246121093472394872319615612417471234712061",
+      "02b9bd7d543685789d57cb918e833af352559021483cdb05cc21fd",
+    ),
+    (
+      "-298472983472983471903246121093472394872319615612417471234712061",
+      "03b9bd7d543685789d57cb918e833af352559021483cdb05cc21fd",
+    ),
+  ];
+}
+
+#[test]
+fn gob_encoding() {
+    for (i, &(s, want)) in ENCODING_TESTS.iter().enumerate() {
+        let x = Int::from_str(s).unwrap();
+
+        let buf = x.gob_encode();
+        assert_eq!(to_hex(&buf), want, "#{i} gob_encode({s})");
+
+        let mut y = Int::new(123);
+        y.gob_decode(&buf).unwrap();
+        assert_eq!(y, x, "#{i} gob_decode({want})");
+        assert!(is_normalized(&y), "#{i} {y} is not normalized");
+    }
+}
+
+#[test]
+fn gob_decode() {
+    let mut z = Int::new(123);
+
+    z.gob_decode(&[]).unwrap();
+    assert_eq!(z, Int::default(), "empty encoding must decode as 0");
+
+    // a negative zero, which Go never emits but accepts
+    z.gob_decode(&[0x03]).unwrap();
+    assert_eq!(z, Int::default());
+    assert!(is_normalized(&z), "{z} is not normalized");
+
+    // leading zero bytes are tolerated
+    z.gob_decode(&[0x03, 0x00, 0x01]).unwrap();
+    assert_eq!(z, Int::new(-1));
+
+    for b in [0x00, 0x01, 0x04, 0x05, 0xff] {
+        let err = z.gob_decode(&[b, 0x01]).unwrap_err();
+        assert_eq!(err, UnmarshalError::UnsupportedGobVersion(b >> 1));
+        assert_eq!(
+            err.to_string(),
+            format!("Int.GobDecode: encoding version {} not supported", b >> 1)
+        );
+    }
+}
+
+#[test]
+fn json_encoding() {
+    for (i, &(s, _)) in ENCODING_TESTS.iter().enumerate() {
+        let x = Int::from_str(s).unwrap();
+
+        let b = x.marshal_json();
+        assert_eq!(b, s.as_bytes(), "#{i} marshal_json({s})");
+
+        let mut y = Int::default();
+        y.unmarshal_json(&b).unwrap();
+        assert_eq!(y, x, "#{i} unmarshal_json({s})");
+    }
+}
+
+#[test]
+fn json_null() {
+    let mut z = Int::new(42);
+    z.unmarshal_json(b"null").unwrap();
+    assert_eq!(z, Int::new(42), "null must leave the value unchanged");
+
+    let err = z.unmarshal_json(b"\"42\"").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        r#"math/big: cannot unmarshal "\"42\"" into a *big.Int"#
+    );
+}
+
+#[test]
+fn text_encoding() {
+    for (i, &(s, _)) in ENCODING_TESTS.iter().enumerate() {
+        let x = Int::from_str(s).unwrap();
+
+        let b = x.marshal_text();
+        assert_eq!(b, s.as_bytes(), "#{i} marshal_text({s})");
+
+        let mut y = Int::default();
+        y.unmarshal_text(&b).unwrap();
+        assert_eq!(y, x, "#{i} unmarshal_text({s})");
+        assert!(is_normalized(&y), "#{i} {y} is not normalized");
+    }
+}
+
+#[test]
+fn unmarshal_text() {
+    // prefixes and separators are accepted, like Go does
+    let test_vector = vec![
+        ("0x_ff", 255),
+        ("-0b101", -5),
+        ("+0o17", 15),
+        ("017", 15),
+        ("1_000", 1000),
+    ];
+
+    for (i, &(s, want)) in test_vector.iter().enumerate() {
+        let mut z = Int::default();
+        z.unmarshal_text(s.as_bytes()).unwrap();
+        assert_eq!(z, Int::new(want), "#{i} unmarshal_text({s})");
+    }
+
+    let invalid: Vec<&[u8]> = vec![
+        b"", b"-", b"0x", b"08", b"1 ", b" 1", b"1_", b"12a", b"1\xff",
+    ];
+
+    for (i, &s) in invalid.iter().enumerate() {
+        let mut z = Int::default();
+        let err = z.unmarshal_text(s).unwrap_err();
+        assert_eq!(err, UnmarshalError::InvalidText(s.to_vec()), "#{i}");
+    }
+
+    let err = Int::default().unmarshal_text(b"x").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        r#"math/big: cannot unmarshal "x" into a *big.Int"#
+    );
+}
+
+fn to_hex(buf: &[u8]) -> String {
+    buf.iter().map(|b| format!("{b:02x}")).collect()
+}
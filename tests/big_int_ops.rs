@@ -0,0 +1,187 @@
+use math::big::Int;
+
+mod helper;
+
+use helper::is_big_int_normalized as is_normalized;
+
+#[test]
+fn arith() {
+    let test_vector = vec![
+        (0i64, 0i64),
+        (1, 0),
+        (123456789, 987654321),
+        (-1, -1),
+        (-123456789, 987654321),
+        (-987654321, -123456789),
+        (i64::MAX, i64::MAX),
+        (i64::MIN, -1),
+    ];
+
+    for (i, &(a, b)) in test_vector.iter().enumerate() {
+        let (x, y) = (Int::new(a), Int::new(b));
+
+        let mut want = Int::default();
+
+        want.add(&x, &y);
+        assert_eq!(&x + &y, want, "#{i} {a} + {b}");
+        assert_eq!(x.clone() + y.clone(), want, "#{i} {a} + {b}");
+        assert_eq!(&x + b, want, "#{i} {a} + {b}");
+        assert_eq!(a + &y, want, "#{i} {a} + {b}");
+
+        want.sub(&x, &y);
+        assert_eq!(&x - &y, want, "#{i} {a} - {b}");
+        assert_eq!(x.clone() - &y, want, "#{i} {a} - {b}");
+        assert_eq!(x.clone() - b, want, "#{i} {a} - {b}");
+        assert_eq!(a - y.clone(), want, "#{i} {a} - {b}");
+
+        want.mul(&x, &y);
+        assert_eq!(&x * &y, want, "#{i} {a} * {b}");
+        assert_eq!(&x * y.clone(), want, "#{i} {a} * {b}");
+        assert_eq!(&x * b, want, "#{i} {a} * {b}");
+        assert_eq!(a * &y, want, "#{i} {a} * {b}");
+
+        let mut z = x.clone();
+        z += &y;
+        z -= y.clone();
+        z *= b;
+        want.mul(&x, &y);
+        assert_eq!(z, want, "#{i} ({a} + {b} - {b}) * {b}");
+        assert!(is_normalized(&z), "#{i} {z} is not normalized");
+    }
+}
+
+#[test]
+fn bitwise() {
+    let test_vector = vec![
+        ("0x00", "0x01"),
+        ("-0x01", "0x00"),
+        ("-0xaf", "-0x50"),
+        ("0xff", "-0x0a"),
+        ("-0x013ff6", "0x9a4e"),
+        (
+            "0x1000009dc6e3d9822cba04129bcbe3401",
+            "-0xb9bd7d543685789d57cb918e833af352559021483cdb05cc21fd",
+        ),
+    ];
+
+    for (i, &(a, b)) in test_vector.iter().enumerate() {
+        let (x, y) = (int_from_str(a), int_from_str(b));
+
+        let mut want = Int::default();
+
+        want.and(&x, &y);
+        assert_eq!(&x & &y, want, "#{i} {a} & {b}");
+        want.or(&x, &y);
+        assert_eq!(&x | y.clone(), want, "#{i} {a} | {b}");
+        want.xor(&x, &y);
+        assert_eq!(x.clone() ^ &y, want, "#{i} {a} ^ {b}");
+        want.not(&x);
+        assert_eq!(!&x, want, "#{i} !{a}");
+        want.neg(&x);
+        assert_eq!(-x.clone(), want, "#{i} -{a}");
+
+        let mut z = x.clone();
+        z &= &y;
+        z |= y.clone();
+        z ^= &x;
+        want.and(&x, &y);
+        want.or(&want.clone(), &y);
+        want.xor(&want.clone(), &x);
+        assert_eq!(z, want, "#{i} (({a} & {b}) | {b}) ^ {a}");
+    }
+
+    let x = Int::new(-0xaf);
+    assert_eq!(&x & 0x0fu64, Int::new(-0xaf & 0x0f));
+    assert_eq!(&x | -0x50i64, Int::new(-0xaf | -0x50));
+    assert_eq!(0xffu32 ^ &x, Int::new(0xff ^ -0xaf));
+}
+
+#[test]
+fn cmp_primitive() {
+    let x = int_from_str("0x10000000000000000");
+
+    assert!(x > u64::MAX);
+    assert!(x > i64::MAX);
+    assert!(-&x < i64::MIN);
+    assert!(u64::MAX < x);
+    assert!(i64::MIN > -x.clone());
+
+    assert_eq!(Int::new(-1), -1i64);
+    assert_ne!(Int::new(-1), u64::MAX);
+    assert_eq!(42u32, Int::new(42));
+    assert_eq!(Int::new(7), 7usize);
+    assert!(Int::new(-7) < 0u32);
+    assert!(Int::new(7) >= 7usize);
+}
+
+#[test]
+fn division_signs() {
+    let test_vector = vec![
+        (5i64, 3i64, 1i64, 2i64),
+        (-5, 3, -1, -2),
+        (5, -3, -1, 2),
+        (-5, -3, 1, -2),
+        (1, 2, 0, 1),
+        (8, 4, 2, 0),
+    ];
+
+    for (i, &(a, b, q, r)) in test_vector.iter().enumerate() {
+        let (x, y) = (Int::new(a), Int::new(b));
+
+        assert_eq!(&x / &y, q, "#{i} {a} / {b}");
+        assert_eq!(&x % &y, r, "#{i} {a} % {b}");
+        assert_eq!(x.clone() / b, q, "#{i} {a} / {b}");
+        assert_eq!(x.clone() % b, r, "#{i} {a} % {b}");
+        assert_eq!(a / y.clone(), q, "#{i} {a} / {b}");
+        assert_eq!(a % &y, r, "#{i} {a} % {b}");
+
+        let mut z = x.clone();
+        z /= &y;
+        assert_eq!(z, q, "#{i} {a} /= {b}");
+        z = x.clone();
+        z %= b;
+        assert_eq!(z, r, "#{i} {a} %= {b}");
+    }
+}
+
+#[test]
+#[should_panic]
+fn division_by_zero() {
+    let _ = Int::new(1) / Int::default();
+}
+
+#[test]
+fn shift() {
+    let test_vector = vec![
+        ("0", 1usize),
+        ("1", 64),
+        ("-87", 1),
+        ("-100", 3),
+        ("18446744073709551616", 64),
+    ];
+
+    for (i, &(s, n)) in test_vector.iter().enumerate() {
+        let x = int_from_str(s);
+
+        let mut want = Int::default();
+
+        want.lsh(&x, n);
+        assert_eq!(&x << n, want, "#{i} {s} << {n}");
+        assert_eq!(x.clone() << (n as u32), want, "#{i} {s} << {n}");
+
+        want.rsh(&x, n);
+        assert_eq!(&x >> n, want, "#{i} {s} >> {n}");
+        assert_eq!(x.clone() >> (n as u32), want, "#{i} {s} >> {n}");
+
+        let mut z = x.clone();
+        z <<= n;
+        z >>= n;
+        assert_eq!(z, x, "#{i} ({s} << {n}) >> {n}");
+    }
+}
+
+fn int_from_str(s: &str) -> Int {
+    let mut out = Int::default();
+    out.set_string(s, 0).expect("set_string");
+    out
+}
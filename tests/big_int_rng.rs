@@ -0,0 +1,66 @@
+#![cfg(feature = "rand_core")]
+
+use std::io::Read;
+
+use math::big::{Int, RngReader};
+use rand::rngs::StdRng;
+use rand::{RngCore, SeedableRng};
+
+#[test]
+fn rng_reader() {
+    let mut rng = StdRng::seed_from_u64(5);
+    let mut r = RngReader(&mut rng);
+
+    let n = Int::new(1000);
+    let (lo, hi) = (Int::new(-10), Int::new(10));
+    let mut z = Int::default();
+    for _ in 0..100 {
+        z.rand(&mut r, &n);
+        assert!(z.sign() >= 0 && z < n, "{z} not in [0, {n})");
+
+        z.rand_bits(&mut r, 70);
+        assert!(
+            z.sign() >= 0 && z.bit_len() <= 70,
+            "{z} has more than 70 bits"
+        );
+
+        z.rand_range(&mut r, &lo, &hi);
+        assert!(z >= lo && z < hi, "{z} not in [{lo}, {hi})");
+    }
+
+    // the same bytes as drawn from the RNG directly
+    let mut want = [0u8; 37];
+    StdRng::seed_from_u64(6).fill_bytes(&mut want);
+    let mut got = [0u8; 37];
+    RngReader(StdRng::seed_from_u64(6))
+        .read_exact(&mut got)
+        .unwrap();
+    assert_eq!(got, want);
+}
+
+#[test]
+#[should_panic(expected = "fill buf")]
+fn rng_reader_error() {
+    struct Broken;
+
+    impl RngCore for Broken {
+        fn next_u32(&mut self) -> u32 {
+            unimplemented!()
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            unimplemented!()
+        }
+
+        fn fill_bytes(&mut self, _: &mut [u8]) {
+            unimplemented!()
+        }
+
+        fn try_fill_bytes(&mut self, _: &mut [u8]) -> Result<(), rand::Error> {
+            Err(rand::Error::new("broken"))
+        }
+    }
+
+    let mut z = Int::default();
+    z.rand(&mut RngReader(Broken), &Int::new(1000));
+}
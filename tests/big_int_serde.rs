@@ -0,0 +1,135 @@
+#![cfg(feature = "serde")]
+
+use std::str::FromStr;
+
+use math::big::Int;
+use serde::{Deserialize, Serialize};
+
+mod helper;
+
+use helper::is_big_int_normalized as is_normalized;
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Config {
+    plain: Int,
+    #[serde(with = "math::big::serde::decimal")]
+    decimal: Int,
+    #[serde(with = "math::big::serde::hex")]
+    hex: Int,
+}
+
+#[test]
+fn bincode() {
+    // (decimal, hex of the bincode encoding: sign, length of the magnitude as u64, magnitude)
+    let test_vector = vec![
+        ("0", "000000000000000000"),
+        ("1", "01010000000000000001"),
+        ("-1", "ff010000000000000001"),
+        ("255", "010100000000000000ff"),
+        ("-256", "ff02000000000000000100"),
+        (
+            "18446744073709551616",
+            "010900000000000000010000000000000000",
+        ),
+    ];
+
+    for (i, &(s, want)) in test_vector.iter().enumerate() {
+        let x = Int::from_str(s).unwrap();
+
+        let buf = bincode::serialize(&x).unwrap();
+        assert_eq!(to_hex(&buf), want, "#{i} serialize({s})");
+
+        let y: Int = bincode::deserialize(&buf).unwrap();
+        assert_eq!(y, x, "#{i} deserialize({want})");
+        assert!(is_normalized(&y), "#{i} {y} is not normalized");
+    }
+
+    // sign and magnitude must agree
+    let invalid: Vec<&[u8]> = vec![
+        &[0x00, 0x01, 0, 0, 0, 0, 0, 0, 0, 0x01],
+        &[0x01, 0x00, 0, 0, 0, 0, 0, 0, 0],
+        &[0xff, 0x01, 0, 0, 0, 0, 0, 0, 0, 0x00],
+        &[0x02, 0x01, 0, 0, 0, 0, 0, 0, 0, 0x01],
+    ];
+
+    for (i, &buf) in invalid.iter().enumerate() {
+        assert!(bincode::deserialize::<Int>(buf).is_err(), "#{i}");
+    }
+}
+
+#[test]
+fn field_helpers() {
+    let c = Config {
+        plain: Int::new(-42),
+        decimal: Int::from_str("123456789012345678901234567890").unwrap(),
+        hex: Int::new(-0xdeadbeef),
+    };
+
+    let s = serde_json::to_string(&c).unwrap();
+    assert_eq!(
+        s,
+        r#"{"plain":"-42","decimal":"123456789012345678901234567890","hex":"-0xdeadbeef"}"#
+    );
+    assert_eq!(serde_json::from_str::<Config>(&s).unwrap(), c);
+
+    // the helpers keep their string form in compact formats too
+    let buf = bincode::serialize(&c).unwrap();
+    assert_eq!(bincode::deserialize::<Config>(&buf).unwrap(), c);
+
+    let c: Config = serde_json::from_str(r#"{"plain":7,"decimal":"+10","hex":"ff"}"#).unwrap();
+    assert_eq!(c.plain, Int::new(7));
+    assert_eq!(c.decimal, Int::new(10));
+    assert_eq!(c.hex, Int::new(0xff));
+
+    let invalid = vec![
+        r#"{"plain":0,"decimal":"0x10","hex":"0"}"#,
+        r#"{"plain":0,"decimal":10,"hex":"0"}"#,
+        r#"{"plain":0,"decimal":"1_0","hex":"0"}"#,
+        r#"{"plain":0,"decimal":"0","hex":"0xg"}"#,
+        r#"{"plain":0,"decimal":"0","hex":"--1"}"#,
+        r#"{"plain":0,"decimal":"0","hex":"-0x"}"#,
+    ];
+
+    for (i, &s) in invalid.iter().enumerate() {
+        assert!(serde_json::from_str::<Config>(s).is_err(), "#{i} {s}");
+    }
+}
+
+#[test]
+fn json() {
+    let test_vector = vec![
+        ("0", "\"0\""),
+        ("-1", "\"-1\""),
+        (
+            "-298472983472983471903246121093472394872319615612417471234712061",
+            "\"-298472983472983471903246121093472394872319615612417471234712061\"",
+        ),
+    ];
+
+    for (i, &(s, want)) in test_vector.iter().enumerate() {
+        let x = Int::from_str(s).unwrap();
+
+        let got = serde_json::to_string(&x).unwrap();
+        assert_eq!(got, want, "#{i} serialize({s})");
+
+        let y: Int = serde_json::from_str(&got).unwrap();
+        assert_eq!(y, x, "#{i} deserialize({want})");
+    }
+
+    // plain JSON integers are accepted too
+    let test_vector = vec![("0", 0i64), ("-9223372036854775808", i64::MIN), ("42", 42)];
+    for (i, &(s, want)) in test_vector.iter().enumerate() {
+        let y: Int = serde_json::from_str(s).unwrap();
+        assert_eq!(y, Int::new(want), "#{i} deserialize({s})");
+    }
+    let y: Int = serde_json::from_str("18446744073709551615").unwrap();
+    assert_eq!(y, Int::from(u64::MAX));
+
+    for s in ["\"\"", "\"1.5\"", "1.5", "\"0x10\"", "null", "[1]"] {
+        assert!(serde_json::from_str::<Int>(s).is_err(), "{s}");
+    }
+}
+
+fn to_hex(buf: &[u8]) -> String {
+    buf.iter().map(|b| format!("{b:02x}")).collect()
+}
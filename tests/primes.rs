@@ -0,0 +1,189 @@
+use std::io::Read;
+
+use math::big::Int;
+use math::primes;
+
+mod helper;
+
+lazy_static::lazy_static! {
+  // the primes below 10^5 by trial division
+  static ref SMALL_PRIMES: Vec<u64> = (2..100_000u64)
+    .filter(|&n| (2..).take_while(|d| d * d <= n).all(|d| n % d != 0))
+    .collect();
+}
+
+#[test]
+fn is_prime_u64() {
+    for n in 0..100_000 {
+        let want = SMALL_PRIMES.binary_search(&n).is_ok();
+        assert_eq!(primes::is_prime_u64(n), want, "is_prime_u64({n})");
+    }
+
+    let composites = vec![
+        // strong pseudoprimes to the first few prime bases
+        2047u64,
+        1373653,
+        25326001,
+        3215031751,
+        2152302898747,
+        3474749660383,
+        341550071728321,
+        3825123056546413051,
+        // Carmichael numbers
+        561,
+        41041,
+        825265,
+        321197185,
+        // (2^32-5)(2^32-17)
+        18446743979220271189,
+        u64::MAX,
+    ];
+    for n in composites {
+        assert!(!primes::is_prime_u64(n), "is_prime_u64({n})");
+    }
+
+    for n in [
+        2305843009213693951u64,
+        18446744073709551557,
+        281474976710677,
+    ] {
+        assert!(primes::is_prime_u64(n), "is_prime_u64({n})");
+    }
+
+    // against the Miller-Rabin and Baillie-PSW tests of big.Int
+    let mut r = helper::rand::Reader::new(23);
+    for _ in 0..2000 {
+        let n = rand_u64(&mut r) | 1;
+        let want = Int::from(n).probably_prime(0);
+        assert_eq!(primes::is_prime_u64(n), want, "is_prime_u64({n})");
+    }
+}
+
+#[test]
+fn iter() {
+    let got: Vec<u64> = primes::iter(..100_000).collect();
+    assert_eq!(got, *SMALL_PRIMES);
+
+    // all kinds of bounds
+    for (lo, hi) in [
+        (0, 0),
+        (0, 2),
+        (2, 3),
+        (3, 3),
+        (10, 30),
+        (7919, 7919),
+        (99_990, 100_000),
+    ] {
+        let want: Vec<u64> = SMALL_PRIMES
+            .iter()
+            .copied()
+            .filter(|p| (lo..=hi).contains(p))
+            .collect();
+        assert_eq!(
+            primes::iter(lo..=hi).collect::<Vec<_>>(),
+            want,
+            "{lo}..={hi}"
+        );
+
+        let want: Vec<u64> = want.into_iter().filter(|&p| p != lo && p != hi).collect();
+        let got: Vec<u64> =
+            primes::iter((std::ops::Bound::Excluded(lo), std::ops::Bound::Excluded(hi))).collect();
+        assert_eq!(got, want, "({lo}, {hi})");
+    }
+
+    // across many segments
+    assert_eq!(primes::iter(0..2_000_000).count(), 148_933);
+
+    // around 2^48, where the sieving primes run out
+    let want = vec![
+        281474976710467u64,
+        281474976710491,
+        281474976710509,
+        281474976710563,
+        281474976710567,
+        281474976710591,
+        281474976710597,
+        281474976710677,
+        281474976710717,
+        281474976710731,
+        281474976710747,
+    ];
+    let got: Vec<u64> = primes::iter((1 << 48) - 200..(1 << 48) + 200).collect();
+    assert_eq!(got, want);
+
+    // up to 2^64-1
+    let want = vec![
+        18446744073709551253u64,
+        18446744073709551263,
+        18446744073709551293,
+        18446744073709551337,
+        18446744073709551359,
+        18446744073709551427,
+        18446744073709551437,
+        18446744073709551521,
+        18446744073709551533,
+        18446744073709551557,
+    ];
+    let got: Vec<u64> = primes::iter(u64::MAX - 400..).collect();
+    assert_eq!(got, want);
+    let got: Vec<u64> = primes::iter(u64::MAX - 400..=u64::MAX).collect();
+    assert_eq!(got, want);
+}
+
+#[test]
+fn nth_prime() {
+    let test_vector = vec![
+        (1u64, 2u64),
+        (2, 3),
+        (3, 5),
+        (1000, 7919),
+        (65535, 821603),
+        (65536, 821641),
+        (1_000_000, 15485863),
+        (10_000_000, 179424673),
+    ];
+
+    for (n, want) in test_vector {
+        assert_eq!(primes::nth_prime(n), want, "nth_prime({n})");
+    }
+
+    for (i, &p) in SMALL_PRIMES.iter().enumerate().step_by(97) {
+        assert_eq!(primes::nth_prime(i as u64 + 1), p);
+    }
+}
+
+#[test]
+#[should_panic(expected = "there is no 0th prime")]
+fn nth_prime_zero() {
+    primes::nth_prime(0);
+}
+
+#[test]
+fn prime_pi() {
+    let want = [
+        0u64, 4, 25, 168, 1229, 9592, 78498, 664579, 5761455, 50847534,
+    ];
+    let mut n = 1;
+    for want in want {
+        assert_eq!(primes::prime_pi(n), want, "prime_pi({n})");
+        n *= 10;
+    }
+
+    for n in (0..100_000).step_by(331) {
+        let want = SMALL_PRIMES.partition_point(|&p| p <= n) as u64;
+        assert_eq!(primes::prime_pi(n), want, "prime_pi({n})");
+    }
+    for n in [2_000_000, 2_000_001, 9_999_991] {
+        assert_eq!(
+            primes::prime_pi(n),
+            primes::iter(..=n).count() as u64,
+            "prime_pi({n})"
+        );
+    }
+}
+
+fn rand_u64<R: Read>(r: &mut R) -> u64 {
+    let mut b = [0u8; 8];
+    r.read_exact(&mut b).unwrap();
+    u64::from_be_bytes(b)
+}